@@ -0,0 +1,132 @@
+;; A car written in the wasm text format, loaded by the tests of the wasm car runner as a
+;; compiled artifact would be. It stores the base car address at instantiation, and on
+;; TakeTurn checks the sender, queries the game state through the chain and buys 1
+;; accelerate. It assumes the messages are serialized as cw-multi-test does, without spaces.
+(module
+  (import "env" "db_read" (func $db_read (param i32) (result i32)))
+  (import "env" "db_write" (func $db_write (param i32 i32)))
+  (import "env" "query_chain" (func $query_chain (param i32) (result i32)))
+
+  (memory (export "memory") 1)
+
+  ;; Allocations are never freed, each call runs on a new instance
+  (global $heap (mut i32) (i32.const 4096))
+  ;; End of the output being written by $emit
+  (global $out (mut i32) (i32.const 0))
+
+  (data (i32.const 1024) "base")
+  (data (i32.const 1028) "{\"ok\":{\"messages\":[],\"attributes\":[{\"key\":\"action\",\"value\":\"instantiate\"}],\"events\":[],\"data\":null}}")
+  (data (i32.const 1128) "{\"wasm\":{\"smart\":{\"contract_addr\":\"")
+  (data (i32.const 1163) "\",\"msg\":\"ImdldF9nYW1lX3N0YXRlIg==\"}}}")
+  (data (i32.const 1200) "{\"ok\":{\"ok\":")
+  (data (i32.const 1212) "{\"ok\":{\"messages\":[{\"id\":0,\"msg\":{\"wasm\":{\"execute\":{\"contract_addr\":\"")
+  (data (i32.const 1282) "\",\"msg\":\"eyJidXkiOnsiYWN0aW9uIjoiYWNjZWxlcmF0ZSIsImFtb3VudCI6MX19\",\"funds\":[]}}},\"gas_limit\":null,\"reply_on\":\"never\"}],\"attributes\":[{\"key\":\"action\",\"value\":\"execute_take_turn\"}],\"events\":[],\"data\":null}}")
+  (data (i32.const 1486) "{\"error\":\"Unauthorized: only the base car can make the car play\"}")
+  (data (i32.const 1551) "{\"error\":\"the game state query failed\"}")
+  (data (i32.const 1590) "{\"error\":\"the car has no queries\"}")
+
+  (func (export "interface_version_8"))
+
+  ;; A region is { offset, capacity, length }, followed by its data
+  (func $allocate (export "allocate") (param $size i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (local.get $ptr) (i32.add (local.get $size) (i32.const 12))))
+    (if (i32.gt_u (global.get $heap) (i32.mul (memory.size) (i32.const 65536)))
+      (then
+        (drop (memory.grow
+          (i32.add (i32.div_u (i32.sub (global.get $heap) (i32.mul (memory.size) (i32.const 65536)))
+                              (i32.const 65536))
+                   (i32.const 1))))))
+    (i32.store (local.get $ptr) (i32.add (local.get $ptr) (i32.const 12)))
+    (i32.store offset=4 (local.get $ptr) (local.get $size))
+    (i32.store offset=8 (local.get $ptr) (i32.const 0))
+    (local.get $ptr))
+
+  (func (export "deallocate") (param i32))
+
+  ;; A region over data already in memory
+  (func $region (param $offset i32) (param $length i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (call $allocate (i32.const 0)))
+    (i32.store (local.get $ptr) (local.get $offset))
+    (i32.store offset=4 (local.get $ptr) (local.get $length))
+    (i32.store offset=8 (local.get $ptr) (local.get $length))
+    (local.get $ptr))
+
+  (func $offset (param $region i32) (result i32)
+    (i32.load (local.get $region)))
+
+  (func $length (param $region i32) (result i32)
+    (i32.load offset=8 (local.get $region)))
+
+  (func $equal (param $a i32) (param $b i32) (param $length i32) (result i32)
+    (block $different
+      (loop $next
+        (if (i32.eqz (local.get $length)) (then (return (i32.const 1))))
+        (br_if $different
+          (i32.ne (i32.load8_u (local.get $a)) (i32.load8_u (local.get $b))))
+        (local.set $a (i32.add (local.get $a) (i32.const 1)))
+        (local.set $b (i32.add (local.get $b) (i32.const 1)))
+        (local.set $length (i32.sub (local.get $length) (i32.const 1)))
+        (br $next)))
+    (i32.const 0))
+
+  ;; The output is written at the end of the heap, nothing is allocated until $end
+  (func $begin
+    (global.set $out (i32.add (global.get $heap) (i32.const 12))))
+
+  (func $emit (param $offset i32) (param $length i32)
+    (memory.copy (global.get $out) (local.get $offset) (local.get $length))
+    (global.set $out (i32.add (global.get $out) (local.get $length))))
+
+  (func $end (result i32)
+    (local $ptr i32)
+    (local.set $ptr
+      (call $allocate (i32.sub (global.get $out) (i32.add (global.get $heap) (i32.const 12)))))
+    (i32.store offset=8 (local.get $ptr) (i32.load offset=4 (local.get $ptr)))
+    (local.get $ptr))
+
+  ;; {"base_car_addr":"<address>"}
+  (func (export "instantiate") (param $env i32) (param $info i32) (param $msg i32) (result i32)
+    (call $db_write
+      (call $region (i32.const 1024) (i32.const 4))
+      (call $region
+        (i32.add (call $offset (local.get $msg)) (i32.const 18))
+        (i32.sub (call $length (local.get $msg)) (i32.const 20))))
+    (call $region (i32.const 1028) (i32.const 100)))
+
+  (func (export "execute") (param $env i32) (param $info i32) (param $msg i32) (result i32)
+    (local $base i32)
+    (local $sender i32)
+    (local.set $base (call $db_read (call $region (i32.const 1024) (i32.const 4))))
+
+    ;; {"sender":"<address>","funds":[..]}
+    (local.set $sender (i32.add (call $offset (local.get $info)) (i32.const 11)))
+    (if (i32.eqz
+          (i32.and
+            (call $equal (local.get $sender) (call $offset (local.get $base)) (call $length (local.get $base)))
+            (i32.eq (i32.load8_u (i32.add (local.get $sender) (call $length (local.get $base))))
+                    (i32.const 34))))
+      (then (return (call $region (i32.const 1486) (i32.const 65)))))
+
+    (call $begin)
+    (call $emit (i32.const 1128) (i32.const 35))
+    (call $emit (call $offset (local.get $base)) (call $length (local.get $base)))
+    (call $emit (i32.const 1163) (i32.const 37))
+    (if (i32.eqz
+          (call $equal
+            (call $offset (call $query_chain (call $end)))
+            (i32.const 1200)
+            (i32.const 12)))
+      (then (return (call $region (i32.const 1551) (i32.const 39)))))
+
+    (call $begin)
+    (call $emit (i32.const 1212) (i32.const 70))
+    (call $emit (call $offset (local.get $base)) (call $length (local.get $base)))
+    (call $emit (i32.const 1282) (i32.const 204))
+    (call $end))
+
+  (func (export "query") (param $env i32) (param $msg i32) (result i32)
+    (call $region (i32.const 1590) (i32.const 34)))
+)
@@ -0,0 +1,293 @@
+#!/usr/bin/env python3
+# Plays the races of this directory on a model of Monaco.sol of 0xTitans and writes their
+# traces, run it from anywhere with `python3 monaco.py`.
+#
+# The model is written from the Solidity rules, independently of titans-engine: prices are
+# computed with 60 significant digits instead of the 18 decimals fixed point math of the
+# contracts, and the turns are played without the base-car messages. It stands in for the
+# recordings of the Solidity game, which need an EVM and the 0xTitans contracts.
+
+import json
+from decimal import ROUND_FLOOR, Decimal, getcontext
+from pathlib import Path
+
+getcontext().prec = 60
+
+WAD = 10**18
+SOURCE = "monaco.py, a model of Monaco.sol of 0xTitans with exact VRGDA prices"
+
+# The defaults of Monaco.sol
+POST_SHELL_SPEED = 1
+STARTING_BALANCE = 17500
+FINISH_DISTANCE = 1000
+
+# target_price, per_turn_decrease and sell_per_turn in WAD
+PRICING = {
+    "accelerate": (10, 330000000000000000, 2 * WAD),
+    "shell": (200, 330000000000000000, WAD // 5),
+    "super_shell": (300, 350000000000000000, WAD // 5),
+    "banana": (200, 330000000000000000, WAD // 5),
+    "shield": (150, 330000000000000000, WAD // 5),
+}
+
+
+def price(action, turns, sold):
+    # target * (1 - decrease) ^ (turns - (sold + 1) / sell_per_turn), rounded down
+    target, decrease, sell = PRICING[action]
+    if target == 0:
+        return 0
+    exponent = Decimal(turns) - Decimal(sold + 1) / (Decimal(sell) / WAD)
+    value = Decimal(target) * (1 - Decimal(decrease) / WAD) ** exponent
+    return int(value.to_integral_value(ROUND_FLOOR))
+
+
+class Car:
+    def __init__(self, name, balance):
+        self.name = name
+        self.balance = balance
+        self.y = 0
+        self.speed = 0
+        self.shield = 0
+
+
+class Monaco:
+    def __init__(self, names, finish_distance=FINISH_DISTANCE, balance=STARTING_BALANCE):
+        self.cars = [Car(name, balance) for name in names]
+        self.finish_distance = finish_distance
+        self.bananas = []
+        self.turns = 0
+        self.sold = {action: 0 for action in PRICING}
+        self.winner = None
+
+    def cost(self, action, amount):
+        sold = self.sold[action]
+        return sum(price(action, self.turns, sold + i) for i in range(amount))
+
+    # The car buys the action, None when it can not pay it: the whole turn is reverted
+    def buy(self, car, action, amount):
+        if action == "banana" and amount != 1:
+            return None
+        cost = self.cost(action, amount)
+        if cost > car.balance:
+            return None
+        car.balance -= cost
+        self.sold[action] += amount
+
+        if action == "accelerate":
+            car.speed += amount
+        elif action == "shell":
+            self.shell(car)
+        elif action == "super_shell":
+            for other in self.cars:
+                if other.y > car.y and other.speed > POST_SHELL_SPEED:
+                    other.speed = POST_SHELL_SPEED
+        elif action == "banana":
+            self.bananas = sorted(self.bananas + [car.y])
+        elif action == "shield":
+            car.shield += 1 + amount
+        return cost
+
+    # The closest car ahead, unless a banana is hit first
+    def shell(self, car):
+        ahead = [other for other in self.cars if other.y > car.y]
+        closest = min(ahead, key=lambda other: other.y) if ahead else None
+        bananas = [banana for banana in self.bananas if banana > car.y]
+        if bananas and (closest is None or bananas[0] <= closest.y):
+            self.bananas.remove(bananas[0])
+            return
+        if closest and closest.shield == 0 and closest.speed > POST_SHELL_SPEED:
+            closest.speed = POST_SHELL_SPEED
+
+    # The turn of the next car, then every car moves in seat order until one finishes
+    def play(self, orders_of):
+        car = self.cars[self.turns % len(self.cars)]
+        actions = []
+        for action, amount in orders_of(self, car):
+            cost = self.buy(car, action, amount)
+            if cost is None:
+                raise ValueError(f"turn {self.turns}: {car.name} can not buy {amount} {action}")
+            actions.append({"action": action, "amount": amount, "cost": cost})
+
+        turn = {"turn": self.turns, "car": car.name, "actions": actions}
+        self.turns += 1
+        for other in self.cars:
+            if other.shield > 0:
+                other.shield -= 1
+            target = other.y + other.speed
+            bananas = [banana for banana in self.bananas if banana > other.y]
+            if bananas and target >= bananas[0]:
+                target = bananas[0]
+                other.speed = 0
+                self.bananas.remove(bananas[0])
+            other.y = target
+            if other.y >= self.finish_distance:
+                self.winner = other.name
+                break
+
+        turn["cars"] = [
+            {
+                "car": other.name,
+                "y": other.y,
+                "speed": other.speed,
+                "shield": other.shield,
+                "balance": other.balance,
+            }
+            for other in self.cars
+        ]
+        return turn
+
+
+# Orders given turn by turn for each car, nothing once the script is over
+def scripted(scripts):
+    played = {}
+
+    def orders_of(game, car):
+        turn = played.get(car.name, 0)
+        played[car.name] = turn + 1
+        script = scripts[car.name]
+        return script[turn] if turn < len(script) else []
+
+    return orders_of
+
+
+# Strategies of the example cars, every car buys what it can pay
+def strategies(by_car):
+    def orders_of(game, car):
+        return by_car[car.name](game, car)
+
+    return orders_of
+
+
+def rank(game, car):
+    return sorted(game.cars, key=lambda other: -other.y).index(car)
+
+
+def accelerator(game, car):
+    # Keep a speed of 8, an acceleration costs at most a tenth of the balance
+    amount = 0
+    while car.speed + amount < 8 and game.cost("accelerate", amount + 1) <= car.balance // 10:
+        amount += 1
+    return [("accelerate", amount)] if amount else []
+
+
+def sheller(game, car):
+    # Shell the car right ahead of us when it is faster, super shell when it is shielded
+    orders = []
+    budget = car.balance // 4
+    ahead = [other for other in game.cars if other.y > car.y]
+    if ahead:
+        closest = min(ahead, key=lambda other: other.y)
+        action = "shell" if closest.shield == 0 else "super_shell"
+        if closest.speed > car.speed and game.cost(action, 1) <= budget:
+            orders.append((action, 1))
+            budget -= game.cost(action, 1)
+    if car.speed < 6 and game.cost("accelerate", 1) <= budget:
+        orders.append(("accelerate", 1))
+    return orders
+
+
+def defender(game, car):
+    # Banana while leading, shield while a car is close behind, cruise at 5
+    orders = []
+    budget = car.balance // 5
+    behind = [other for other in game.cars if other.y < car.y]
+    if rank(game, car) == 0 and behind and car.y not in game.bananas:
+        if game.cost("banana", 1) <= budget:
+            orders.append(("banana", 1))
+            budget -= game.cost("banana", 1)
+    close = [other for other in behind if car.y - other.y <= 30]
+    if close and car.shield == 0 and game.cost("shield", 1) <= budget:
+        orders.append(("shield", 1))
+        budget -= game.cost("shield", 1)
+    if car.speed < 5 and game.cost("accelerate", 1) <= budget:
+        orders.append(("accelerate", 1))
+    return orders
+
+
+RACES = [
+    {
+        "name": "banana-and-shell",
+        "cars": ["car-0", "car-1"],
+        "finish_distance": 30,
+        "orders": scripted(
+            {
+                "car-0": [[("accelerate", 3)], [("banana", 1)], [], [("accelerate", 4)]],
+                "car-1": [[("accelerate", 5)], [("accelerate", 2)], [("shell", 1)]],
+            }
+        ),
+    },
+    {
+        "name": "super-shell-and-shield",
+        "cars": ["car-0", "car-1", "car-2"],
+        "finish_distance": 60,
+        "orders": scripted(
+            {
+                "car-0": [[("accelerate", 4)], [("shield", 1)], [], [("accelerate", 1)]],
+                "car-1": [[("accelerate", 6)], [], [("shield", 2)], [("banana", 1)]],
+                "car-2": [[("accelerate", 2)], [("shell", 1)], [("super_shell", 1)], [("accelerate", 5)]],
+            }
+        ),
+    },
+    {
+        "name": "example-strategies",
+        "cars": ["accelerator", "sheller", "defender"],
+        "finish_distance": FINISH_DISTANCE,
+        "orders": strategies({"accelerator": accelerator, "sheller": sheller, "defender": defender}),
+    },
+]
+
+
+def record(race):
+    game = Monaco(race["cars"], race["finish_distance"])
+    turns = []
+    while game.winner is None:
+        if game.turns >= 2000:
+            raise ValueError(f"{race['name']}: no winner after 2000 turns")
+        turns.append(game.play(race["orders"]))
+
+    config = {}
+    if race["finish_distance"] != FINISH_DISTANCE:
+        config["target_distance"] = race["finish_distance"]
+    return {
+        "name": race["name"],
+        "source": SOURCE,
+        "config": config,
+        "cars": race["cars"],
+        "turns": turns,
+        "winner": game.winner,
+    }
+
+
+# One action and one car per line, as the hand-written traces
+def dump(trace):
+    def line(value):
+        value = json.dumps(value, separators=(", ", ": "))
+        return value.replace("{", "{ ").replace("}", " }").replace("{  }", "{}")
+
+    lines = ["{"]
+    for key in ["name", "source", "config", "cars"]:
+        lines.append(f'  "{key}": {line(trace[key])},')
+    lines.append('  "turns": [')
+    for i, turn in enumerate(trace["turns"]):
+        actions = ", ".join(line(action) for action in turn["actions"])
+        cars = ",\n".join(f"        {line(car)}" for car in turn["cars"])
+        lines += [
+            "    {",
+            f'      "turn": {turn["turn"]},',
+            f'      "car": "{turn["car"]}",',
+            f'      "actions": [{actions}],',
+            '      "cars": [',
+            cars,
+            "      ]",
+            "    }" + ("," if i + 1 < len(trace["turns"]) else ""),
+        ]
+    lines += ["  ],", f'  "winner": "{trace["winner"]}"', "}"]
+    return "\n".join(lines) + "\n"
+
+
+if __name__ == "__main__":
+    directory = Path(__file__).resolve().parent
+    for race in RACES:
+        trace = record(race)
+        (directory / f"{race['name']}.json").write_text(dump(trace))
+        print(f"{race['name']}: {len(trace['turns'])} turns, won by {trace['winner']}")
@@ -0,0 +1,447 @@
+// Race between the example cars on a cw-multi-test App, with a turn by turn table of the
+// race and a JSON summary
+use std::{cell::RefCell, fs, path::Path, rc::Rc};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Attribute, Empty};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use titans_sim::cli::{exit, Args, Preset};
+
+use base_car::{
+    contract::{execute, instantiate, query, reply},
+    msg::{ExecuteMsg, GameStateResponse, InstantiateMsg, QueryMsg, StandingsResponse},
+    state::{CarData, Config, State},
+    wasm_car::{GasLog, WasmCar},
+};
+
+const USAGE: &str = "Usage: cargo race -- [--config config.json] [--preset presets/NAME.json] [--max-turns N] [--summary summary.json] [CAR...]
+
+Cars: car-1, car-2, car-3, or the path of a compiled car.wasm (default: the example cars,
+2 to 8 cars, in seat order)
+A preset is saved on the base contract under the name of the file and used for the race,
+it sets the number of cars";
+
+const EXAMPLE_CARS: [&str; 3] = ["car-1", "car-2", "car-3"];
+
+#[cw_serde]
+struct CarInstantiateMsg {
+    base_car_addr: Addr,
+}
+
+#[cw_serde]
+struct ActionLog {
+    action: String,
+    amount: u64,
+    cost: u64,
+    // Cars slowed down by the action
+    shelled: Vec<String>,
+}
+
+#[cw_serde]
+struct CarLog {
+    car: String,
+    y: u64,
+    speed: u64,
+    shield: u64,
+    balance: u64,
+}
+
+#[cw_serde]
+struct TurnLog {
+    turn: u64,
+    car: String,
+    actions: Vec<ActionLog>,
+    // Why the car lost its turn
+    error: Option<String>,
+    // Gas of the TakeTurn of a compiled car
+    gas: Option<u64>,
+    // Cars after the turn, in seat order
+    cars: Vec<CarLog>,
+}
+
+#[cw_serde]
+struct RaceSummary {
+    config: Config,
+    // Preset the config comes from
+    preset: Option<String>,
+    // Cars in seat order
+    cars: Vec<String>,
+    winner: Option<String>,
+    turns: u64,
+    // Cars from the first to the last one
+    standings: Vec<CarLog>,
+    turn_log: Vec<TurnLog>,
+}
+
+struct Race {
+    app: App,
+    owner: Addr,
+    base_car: Addr,
+    // Address and name of each car, in seat order
+    cars: Vec<(Addr, String)>,
+    // Gas of the calls to the compiled cars
+    gas_logs: Vec<Rc<RefCell<Vec<GasLog>>>>,
+}
+
+fn main() {
+    let mut config = Config::default();
+    let mut max_turns = 1000u64;
+    let mut summary_path = "race-summary.json".to_string();
+    let mut names = vec![];
+    let mut preset = None;
+
+    let mut args = Args::from_env(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => config = args.json(&arg),
+            "--preset" => {
+                let Preset {
+                    name,
+                    config: saved,
+                } = args.preset(&arg);
+                config = saved;
+                preset = Some(name);
+            }
+            "--max-turns" => max_turns = args.value(&arg),
+            "--summary" => summary_path = args.value(&arg),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            name => names.push(name.to_string()),
+        }
+    }
+
+    if names.is_empty() {
+        let count = match preset {
+            Some(_) => config.num_players as usize,
+            None => EXAMPLE_CARS.len(),
+        };
+        names = EXAMPLE_CARS
+            .iter()
+            .cycle()
+            .take(count)
+            .map(|name| name.to_string())
+            .collect();
+    }
+    if preset.is_some() && config.num_players != names.len() as u64 {
+        args.exit("the number of cars does not match num_players of the preset");
+    }
+    if names.len() < 2 || names.len() > 8 {
+        args.exit("a race needs between 2 and 8 cars");
+    }
+    if config.shuffle_turn_order {
+        args.exit("the runner plays the cars in seat order, set shuffle_turn_order to false");
+    }
+    config.num_players = names.len() as u64;
+
+    let mut race = Race::new(config.clone(), preset, &names);
+    let turn_log = race.run(max_turns);
+    print_table(&race, &turn_log);
+
+    let game_state = race.game_state();
+    let standings = race.standings();
+    let summary = RaceSummary {
+        config,
+        preset: game_state.preset.clone(),
+        cars: race.cars.iter().map(|(_, name)| name.clone()).collect(),
+        winner: (game_state.state == State::Done)
+            .then(|| race.car_name(standings[0].addr.as_str())),
+        turns: game_state.turns,
+        standings: standings.iter().map(|car| race.car_log(car)).collect(),
+        turn_log,
+    };
+
+    match &summary.winner {
+        Some(winner) => println!("\n{} wins after {} turns", winner, summary.turns),
+        None => println!("\nno winner after {} turns", summary.turns),
+    }
+    let data = serde_json::to_string_pretty(&summary).unwrap();
+    fs::write(&summary_path, data).unwrap_or_else(|err| args.exit(&err.to_string()));
+    println!("summary written to {}", summary_path);
+}
+
+impl Race {
+    // The config is used directly, or saved as a preset first
+    fn new(config: Config, preset: Option<String>, names: &[String]) -> Self {
+        let mut app = App::default();
+        let owner = Addr::unchecked("owner");
+
+        let code_id = app.store_code(contract_base_car());
+        let base_car = app
+            .instantiate_contract(
+                code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    owner: owner.to_string(),
+                },
+                &[],
+                "base-car",
+                None,
+            )
+            .unwrap();
+        let msgs = match preset {
+            Some(name) => vec![
+                ExecuteMsg::SavePreset {
+                    name: name.clone(),
+                    config: Box::new(config),
+                },
+                ExecuteMsg::UsePreset { name },
+            ],
+            None => vec![ExecuteMsg::UpdateConfig {
+                config: Box::new(config),
+            }],
+        };
+        for msg in msgs {
+            app.execute_contract(owner.clone(), base_car.clone(), &msg, &[])
+                .unwrap_or_else(|err| exit(USAGE, &err.root_cause().to_string()));
+        }
+
+        let mut cars = vec![];
+        let mut gas_logs = vec![];
+        for (seat, name) in names.iter().enumerate() {
+            let contract = match example_car(name) {
+                Some(contract) => contract,
+                None => {
+                    let car = wasm_car(name);
+                    gas_logs.push(car.gas_log());
+                    Box::new(car)
+                }
+            };
+            let code_id = app.store_code(contract);
+            let addr = app
+                .instantiate_contract(
+                    code_id,
+                    owner.clone(),
+                    &CarInstantiateMsg {
+                        base_car_addr: base_car.clone(),
+                    },
+                    &[],
+                    name.clone(),
+                    None,
+                )
+                .unwrap();
+
+            // Compiled cars are named after their file, the same car can race several times
+            let mut car_name = match Path::new(name).extension() {
+                Some(_) => Path::new(name)
+                    .file_stem()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string(),
+                None => name.clone(),
+            };
+            if names.iter().filter(|other| *other == name).count() > 1 {
+                car_name = format!("{}#{}", car_name, seat);
+            }
+            cars.push((addr, car_name));
+        }
+
+        app.execute_contract(
+            owner.clone(),
+            base_car.clone(),
+            &ExecuteMsg::Register {
+                car_addrs: cars.iter().map(|(addr, _)| addr.clone()).collect(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        Self {
+            app,
+            owner,
+            base_car,
+            cars,
+            gas_logs,
+        }
+    }
+
+    // Play the race one turn at a time
+    fn run(&mut self, max_turns: u64) -> Vec<TurnLog> {
+        let mut turn_log = vec![];
+
+        while self.game_state().state == State::Active && self.game_state().turns < max_turns {
+            let turn = self.game_state().turns;
+            let res = self
+                .app
+                .execute_contract(
+                    self.owner.clone(),
+                    self.base_car.clone(),
+                    &ExecuteMsg::Play { turns_to_play: 1 },
+                    &[],
+                )
+                .unwrap_or_else(|err| exit(USAGE, &err.root_cause().to_string()));
+            let attributes: Vec<&Attribute> = res
+                .events
+                .iter()
+                .flat_map(|event| event.attributes.iter())
+                .collect();
+
+            let value = |attrs: &[&Attribute], key: &str| {
+                attrs
+                    .iter()
+                    .find(|attr| attr.key == key)
+                    .map(|attr| attr.value.clone())
+            };
+
+            // The attributes of each buy start with the `bought` action
+            let mut actions = vec![];
+            let starts: Vec<usize> = attributes
+                .iter()
+                .enumerate()
+                .filter(|(_, attr)| attr.key == "bought")
+                .map(|(i, _)| i)
+                .collect();
+            for (n, start) in starts.iter().enumerate() {
+                let end = starts.get(n + 1).copied().unwrap_or(attributes.len());
+                let attrs = &attributes[*start..end];
+                actions.push(ActionLog {
+                    action: attrs[0].value.clone(),
+                    amount: value(attrs, "amount").unwrap_or_default().parse().unwrap(),
+                    cost: value(attrs, "cost").unwrap_or_default().parse().unwrap(),
+                    shelled: value(attrs, "shelled")
+                        .map(|shelled| shelled.split('|').map(|car| self.car_name(car)).collect())
+                        .unwrap_or_default(),
+                });
+            }
+
+            let car = value(&attributes, "car").unwrap_or_default();
+            let standings = self.standings();
+            turn_log.push(TurnLog {
+                turn,
+                car: self.car_name(&car),
+                actions,
+                error: value(&attributes, "car_error"),
+                gas: self.take_gas(),
+                cars: self
+                    .cars
+                    .iter()
+                    .filter_map(|(addr, _)| standings.iter().find(|car| car.addr == addr))
+                    .map(|car| self.car_log(car))
+                    .collect(),
+            });
+        }
+
+        turn_log
+    }
+
+    // Gas of the TakeTurn calls since the last turn, if a compiled car played
+    fn take_gas(&self) -> Option<u64> {
+        let calls: Vec<GasLog> = self
+            .gas_logs
+            .iter()
+            .flat_map(|gas_log| gas_log.borrow_mut().drain(..).collect::<Vec<_>>())
+            .filter(|call| call.entry_point == "execute")
+            .collect();
+        (!calls.is_empty()).then(|| calls.iter().map(|call| call.gas).sum())
+    }
+
+    fn game_state(&self) -> GameStateResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.base_car, &QueryMsg::GetGameState)
+            .unwrap()
+    }
+
+    fn standings(&self) -> Vec<CarData> {
+        let res: StandingsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.base_car, &QueryMsg::GetStandings)
+            .unwrap();
+        res.cars
+    }
+
+    fn car_name(&self, addr: &str) -> String {
+        self.cars
+            .iter()
+            .find(|(car, _)| car.as_str() == addr)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| addr.to_string())
+    }
+
+    fn car_log(&self, car: &CarData) -> CarLog {
+        CarLog {
+            car: self.car_name(car.addr.as_str()),
+            y: car.y,
+            speed: car.speed,
+            shield: car.shield,
+            balance: car.balance,
+        }
+    }
+}
+
+fn contract_base_car() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+fn example_car(name: &str) -> Option<Box<dyn Contract<Empty>>> {
+    let contract: Box<dyn Contract<Empty>> = match name {
+        "car-1" => Box::new(ContractWrapper::new(
+            car_1::contract::execute,
+            car_1::contract::instantiate,
+            car_1::contract::query,
+        )),
+        "car-2" => Box::new(ContractWrapper::new(
+            car_2::contract::execute,
+            car_2::contract::instantiate,
+            car_2::contract::query,
+        )),
+        "car-3" => Box::new(ContractWrapper::new(
+            car_3::contract::execute,
+            car_3::contract::instantiate,
+            car_3::contract::query,
+        )),
+        _ => return None,
+    };
+    Some(contract)
+}
+
+fn wasm_car(path: &str) -> WasmCar {
+    let wasm = fs::read(path).unwrap_or_else(|_| exit(USAGE, &format!("unknown car {}", path)));
+    WasmCar::new(&wasm).unwrap_or_else(|err| exit(USAGE, &format!("{}: {}", path, err)))
+}
+
+// One line per turn: the car playing, its actions and the gas of a compiled car, then
+// `y/speed $balance` of each car
+fn print_table(race: &Race, turn_log: &[TurnLog]) {
+    print!("{:>5}  {:<8} {:<44}", "turn", "car", "actions");
+    for (_, name) in race.cars.iter() {
+        print!(" {:>18}", name);
+    }
+    println!();
+
+    for turn in turn_log {
+        let actions = match &turn.error {
+            Some(_) => "turn lost".to_string(),
+            None => turn
+                .actions
+                .iter()
+                .map(|action| {
+                    let shelled = if action.shelled.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" >{}", action.shelled.join(","))
+                    };
+                    format!(
+                        "{} x{} ${}{}",
+                        action.action, action.amount, action.cost, shelled
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        let actions = match turn.gas {
+            Some(gas) => format!("{} gas:{}", actions, gas),
+            None => actions,
+        };
+        print!("{:>5}  {:<8} {:<44}", turn.turn, turn.car, actions);
+        for car in turn.cars.iter() {
+            print!(
+                " {:>18}",
+                format!("{}/{} ${}", car.y, car.speed, car.balance)
+            );
+        }
+        println!();
+    }
+}
@@ -0,0 +1,326 @@
+#![cfg(test)]
+
+// Replay of the race traces of `fixtures/traces` through base-car, see the README there
+use std::{fmt, fs, path::PathBuf};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::from_slice;
+
+use crate::integration_tests::RaceFixture;
+use crate::state::{ActionConfig, CarData, Config};
+
+#[cw_serde]
+struct Trace {
+    name: String,
+    source: String,
+    config: TraceConfig,
+    cars: Vec<String>,
+    turns: Vec<TraceTurn>,
+    winner: Option<String>,
+}
+
+#[cw_serde]
+struct TraceConfig {
+    post_sell_speed: Option<u64>,
+    init_balance: Option<u64>,
+    target_distance: Option<u64>,
+    banana_speed_modifier: Option<u64>,
+    actions: Option<Vec<ActionConfig>>,
+}
+
+#[cw_serde]
+struct TraceTurn {
+    turn: u64,
+    car: String,
+    actions: Vec<TraceAction>,
+    cars: Vec<TraceCar>,
+}
+
+#[cw_serde]
+struct TraceAction {
+    action: String,
+    amount: u64,
+    cost: u64,
+}
+
+// A car after the turn
+#[cw_serde]
+struct TraceCar {
+    car: String,
+    y: u64,
+    speed: u64,
+    shield: u64,
+    balance: u64,
+}
+
+impl Trace {
+    fn config(&self) -> Config {
+        let default = Config::default();
+        Config {
+            num_players: self.cars.len() as u64,
+            post_sell_speed: self
+                .config
+                .post_sell_speed
+                .unwrap_or(default.post_sell_speed),
+            init_balance: self.config.init_balance.unwrap_or(default.init_balance),
+            target_distance: self
+                .config
+                .target_distance
+                .unwrap_or(default.target_distance),
+            banana_speed_modifier: self
+                .config
+                .banana_speed_modifier
+                .unwrap_or(default.banana_speed_modifier),
+            actions: self
+                .config
+                .actions
+                .clone()
+                .unwrap_or(default.actions.clone()),
+            ..default
+        }
+    }
+
+    // Orders of each car, one list per turn of the car
+    fn scripts(&self) -> Vec<Vec<Vec<(String, u64)>>> {
+        self.cars
+            .iter()
+            .map(|car| {
+                self.turns
+                    .iter()
+                    .filter(|turn| &turn.car == car)
+                    .map(|turn| {
+                        turn.actions
+                            .iter()
+                            .map(|action| (action.action.clone(), action.amount))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// A difference between the trace and base-car
+#[derive(Debug, PartialEq)]
+struct Divergence {
+    turn: u64,
+    subject: String,
+    expected: String,
+    actual: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "turn {} {}: expected {}, got {}",
+            self.turn, self.subject, self.expected, self.actual
+        )
+    }
+}
+
+struct Replay {
+    divergences: Vec<Divergence>,
+}
+
+impl Replay {
+    fn check<T: PartialEq + fmt::Display>(
+        &mut self,
+        turn: u64,
+        subject: String,
+        expected: T,
+        actual: T,
+    ) {
+        if expected != actual {
+            self.divergences.push(Divergence {
+                turn,
+                subject,
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            });
+        }
+    }
+}
+
+// Play the trace turn by turn, the first car missing from the race stops the replay
+fn replay(trace: &Trace) -> Vec<Divergence> {
+    let mut fixture = RaceFixture::scripted(trace.config(), trace.scripts());
+    let addrs = fixture.cars.clone();
+    let name_of = |addr: &str| {
+        addrs
+            .iter()
+            .position(|car| car.as_str() == addr)
+            .map(|i| trace.cars[i].clone())
+            .unwrap_or_else(|| addr.to_string())
+    };
+    let addr_of = |name: &str| {
+        trace
+            .cars
+            .iter()
+            .position(|car| car == name)
+            .map(|i| addrs[i].clone())
+    };
+    let mut replay = Replay {
+        divergences: vec![],
+    };
+    let mut winner = None;
+
+    for turn in trace.turns.iter() {
+        let res = fixture.play(1);
+        let attributes: Vec<_> = res
+            .events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .collect();
+
+        let car = attributes
+            .iter()
+            .find(|attr| attr.key == "car")
+            .map(|attr| name_of(&attr.value))
+            .unwrap_or_default();
+        replay.check(turn.turn, "car".to_string(), &turn.car, &car);
+        if let Some(attr) = attributes.iter().find(|attr| attr.key == "winner") {
+            winner = Some(name_of(&attr.value));
+        }
+        if let Some(error) = attributes.iter().find(|attr| attr.key == "car_error") {
+            replay.check(
+                turn.turn,
+                format!("{} error", turn.car),
+                "none",
+                error.value.as_str(),
+            );
+        }
+
+        let costs: Vec<u64> = attributes
+            .iter()
+            .filter(|attr| attr.key == "cost")
+            .map(|attr| attr.value.parse().unwrap())
+            .collect();
+        for (i, action) in turn.actions.iter().enumerate() {
+            let actual = costs
+                .get(i)
+                .map(|cost| cost.to_string())
+                .unwrap_or_else(|| "nothing".to_string());
+            replay.check(
+                turn.turn,
+                format!("{} {} cost", turn.car, action.action),
+                action.cost.to_string(),
+                actual,
+            );
+        }
+        replay.check(
+            turn.turn,
+            format!("{} purchases", turn.car),
+            turn.actions.len(),
+            costs.len(),
+        );
+
+        // Every car is checked after every turn
+        let standings = fixture.standings();
+        replay.check(
+            turn.turn,
+            "cars".to_string(),
+            standings.len(),
+            turn.cars.len(),
+        );
+        for expected in turn.cars.iter() {
+            let actual = addr_of(&expected.car)
+                .and_then(|addr| standings.iter().find(|car| car.addr == addr));
+            let Some(actual) = actual else {
+                replay.check(turn.turn, expected.car.clone(), "in the race", "missing");
+                continue;
+            };
+            check_car(&mut replay, turn.turn, expected, actual);
+        }
+    }
+
+    let state = fixture.game_state();
+    replay.check(
+        state.turns,
+        "turns".to_string(),
+        trace.turns.len() as u64,
+        state.turns,
+    );
+    replay.check(
+        state.turns,
+        "winner".to_string(),
+        trace.winner.clone().unwrap_or_else(|| "none".to_string()),
+        winner.unwrap_or_else(|| "none".to_string()),
+    );
+
+    replay.divergences
+}
+
+fn check_car(replay: &mut Replay, turn: u64, expected: &TraceCar, actual: &CarData) {
+    let car = &expected.car;
+    replay.check(turn, format!("{} y", car), expected.y, actual.y);
+    replay.check(turn, format!("{} speed", car), expected.speed, actual.speed);
+    replay.check(
+        turn,
+        format!("{} shield", car),
+        expected.shield,
+        actual.shield,
+    );
+    replay.check(
+        turn,
+        format!("{} balance", car),
+        expected.balance,
+        actual.balance,
+    );
+}
+
+fn traces() -> Vec<Trace> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/traces");
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            from_slice(&fs::read(path).unwrap())
+                .unwrap_or_else(|err| panic!("invalid trace {}: {}", path.display(), err))
+        })
+        .collect()
+}
+
+#[test]
+fn test_conformance_traces() {
+    let traces = traces();
+    assert!(!traces.is_empty());
+
+    let mut report = vec![];
+    for trace in traces.iter() {
+        for divergence in replay(trace) {
+            report.push(format!("{} ({}): {}", trace.name, trace.source, divergence));
+        }
+    }
+    assert!(report.is_empty(), "\n{}", report.join("\n"));
+}
+
+#[test]
+fn test_divergence_report() {
+    let mut trace = traces()
+        .into_iter()
+        .find(|trace| trace.name == "banana-and-shell")
+        .unwrap();
+    trace.turns[5].cars[0].speed = 3;
+    trace.turns[5].actions[0].cost = 2;
+    trace.turns[6].cars.pop();
+
+    let divergences = replay(&trace);
+    assert_eq!(
+        divergences
+            .iter()
+            .map(|divergence| divergence.to_string())
+            .collect::<Vec<_>>(),
+        vec![
+            "turn 5 car-1 shell cost: expected 2, got 200",
+            "turn 5 car-0 speed: expected 3, got 1",
+            "turn 6 cars: expected 2, got 1",
+        ]
+    );
+}
@@ -1,26 +1,28 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+};
 
 use crate::error::ContractError;
+use crate::helpers::load_race;
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{ActionType, CarData, GameState, ACTION_SOLD, ALL_CAR_DATA, GAME_STATE, OWNER};
+use crate::state::{
+    CarData, GameState, ACCELERATE, BANANA, GAME_STATE, OWNER, SHELL, SHIELD, SUPER_SHELL,
+};
+
+// Reply of the TakeTurn sub message sent to the car playing the current turn
+pub const TAKE_TURN_REPLY_ID: u64 = 1;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
-    _msg: InstantiateMsg,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    let owner = info.sender.clone();
-    OWNER.save(deps.storage, &owner.to_string())?;
-
-    ACTION_SOLD.save(deps.storage, &ActionType::Accelerate.to_string(), &0)?;
-    ACTION_SOLD.save(deps.storage, &ActionType::Shell.to_string(), &0)?;
-    ACTION_SOLD.save(deps.storage, &ActionType::SuperShell.to_string(), &0)?;
-    ACTION_SOLD.save(deps.storage, &ActionType::Banana.to_string(), &0)?;
-    ACTION_SOLD.save(deps.storage, &ActionType::Shield.to_string(), &0)?;
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    OWNER.save(deps.storage, &owner)?;
 
     let game_state = GameState::default();
     GAME_STATE.save(deps.storage, &game_state)?;
@@ -40,32 +42,70 @@ pub fn execute(
     match msg {
         ExecuteMsg::Register { car_addrs } => execute::execute_register(deps, env, info, car_addrs),
         ExecuteMsg::Play { turns_to_play } => execute::execute_play(deps, env, info, turns_to_play),
-        ExecuteMsg::BuyShell { amount } => execute::execute_buy_shell(deps, env, info, amount),
+        ExecuteMsg::Crank { turns } => execute::execute_crank(deps, env, info, turns),
+        ExecuteMsg::FundCrankPool {} => execute::execute_fund_crank_pool(deps, env, info),
+        ExecuteMsg::Buy { action, amount } => execute::execute_buy(deps, env, info, action, amount),
+        ExecuteMsg::BuyShell { amount } => {
+            execute::execute_buy(deps, env, info, SHELL.to_string(), amount)
+        }
         ExecuteMsg::BuyAccelerate { amount } => {
-            execute::execute_buy_accelerate(deps, env, info, amount)
+            execute::execute_buy(deps, env, info, ACCELERATE.to_string(), amount)
+        }
+        ExecuteMsg::BuyBanana {} => execute::execute_buy(deps, env, info, BANANA.to_string(), 1),
+        ExecuteMsg::BuyShield { amount } => {
+            execute::execute_buy(deps, env, info, SHIELD.to_string(), amount)
         }
-        ExecuteMsg::BuyBanana {} => execute::execute_buy_banana(deps, env, info),
-        ExecuteMsg::BuyShield { amount } => execute::execute_buy_shield(deps, env, info, amount),
         ExecuteMsg::BuySuperShell { amount } => {
-            execute::execute_buy_super_shell(deps, env, info, amount)
+            execute::execute_buy(deps, env, info, SUPER_SHELL.to_string(), amount)
         }
         ExecuteMsg::Reset {} => execute::execute_reset(deps, env, info),
+        ExecuteMsg::ProposeOwner { new_owner } => {
+            execute::execute_propose_owner(deps, env, info, new_owner)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute::execute_accept_ownership(deps, env, info),
+        ExecuteMsg::AddOperator { operator } => {
+            execute::execute_add_operator(deps, env, info, operator)
+        }
+        ExecuteMsg::RemoveOperator { operator } => {
+            execute::execute_remove_operator(deps, env, info, operator)
+        }
+        ExecuteMsg::UpdateConfig { config } => {
+            execute::execute_update_config(deps, env, info, *config)
+        }
+        ExecuteMsg::SavePreset { name, config } => {
+            execute::execute_save_preset(deps, env, info, name, *config)
+        }
+        ExecuteMsg::DeletePreset { name } => execute::execute_delete_preset(deps, env, info, name),
+        ExecuteMsg::UsePreset { name } => execute::execute_use_preset(deps, env, info, name),
+        ExecuteMsg::Pause {} => execute::execute_set_paused(deps, env, info, true),
+        ExecuteMsg::Unpause {} => execute::execute_set_paused(deps, env, info, false),
+        ExecuteMsg::Abort { reason } => execute::execute_abort(deps, env, info, reason),
+        ExecuteMsg::CommitSeed { commitment } => {
+            execute::execute_commit_seed(deps, env, info, commitment)
+        }
+        ExecuteMsg::RevealSeed { secret } => execute::execute_reveal_seed(deps, env, info, secret),
     }
 }
 
 pub mod execute {
     use std::vec;
 
-    use cosmwasm_std::{to_binary, Addr, CosmosMsg, DepsMut, Env, MessageInfo, Response, WasmMsg};
+    use cosmwasm_std::{
+        coins, Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage,
+        Uint128,
+    };
 
     use crate::{
         helpers::{
-            get_accel_cost, get_banana_cost, get_bananas_sorted_by_y, get_shell_cost,
-            get_shield_cost, get_super_shell_cost,
+            assert_not_in_turn, assert_not_paused, assert_owner, assert_owner_or_operator,
+            assert_valid_config, get_turn_order_seed, load_race, save_race, sha256_hex,
+            take_turn_msg,
         },
-        msg::CarExecuteMsg,
         state::{
-            ActionType, CarData, GameState, State, ACTION_SOLD, ALL_CAR_DATA, GAME_STATE, OWNER,
+            CarData, Config, GameState, PlayContext, RaceRecord, SeedReveal, State, ACTION_SOLD,
+            ALL_CAR_DATA, CAR_ACTION_SOLD, CRANK_DEPOSITS, CRANK_POOL, GAME_STATE, OPERATORS,
+            OWNER, PAUSED, PENDING_OWNER, PLAY_CONTEXT, PRESETS, RACE_HISTORY, SEED_COMMITS,
+            SEED_REVEALS, TURN_LOCK,
         },
         ContractError,
     };
@@ -75,449 +115,808 @@ pub mod execute {
         _env: Env,
         info: MessageInfo,
     ) -> Result<Response, ContractError> {
-        let owner = OWNER.load(deps.storage)?;
+        assert_owner_or_operator(deps.storage, &info.sender)?;
+        assert_not_in_turn(deps.storage)?;
+
+        // Archive the race so it is still queryable
+        let old_game_state = GAME_STATE.load(deps.storage)?;
+        let cars = old_game_state
+            .all_cars
+            .iter()
+            .map(|car_addr| ALL_CAR_DATA.load(deps.storage, car_addr.clone()))
+            .collect::<StdResult<Vec<CarData>>>()?;
+        for car_addr in old_game_state.all_cars.iter() {
+            ALL_CAR_DATA.remove(deps.storage, car_addr.clone());
+        }
+        RACE_HISTORY.save(
+            deps.storage,
+            old_game_state.game_id,
+            &RaceRecord {
+                game: old_game_state.clone(),
+                cars,
+            },
+        )?;
 
-        if info.sender.to_string() != owner {
-            return Err(ContractError::Unauthorized {});
+        // The pool of a race is not carried over to the next one
+        let (refunded, refund_msgs) = refund_crank_pool(deps.storage, &old_game_state)?;
+
+        // Prices start over with the sold counters
+        ACTION_SOLD.clear(deps.storage);
+        CAR_ACTION_SOLD.clear(deps.storage);
+
+        // Commits and reveals only count for the race they were made for
+        let committed = SEED_COMMITS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Addr>>>()?;
+        for addr in committed {
+            SEED_COMMITS.remove(deps.storage, addr.clone());
+            SEED_REVEALS.remove(deps.storage, addr);
         }
 
-        let game_state = GameState::default();
+        // The config is kept for the next race
+        let game_state = GameState {
+            game_id: old_game_state.game_id + 1,
+            config: old_game_state.config,
+            preset: old_game_state.preset,
+            ..GameState::default()
+        };
         GAME_STATE.save(deps.storage, &game_state)?;
-        Ok(Response::new().add_attribute("action", "execute_reset"))
+        Ok(Response::new()
+            .add_messages(refund_msgs)
+            .add_attribute("game_id", game_state.game_id.to_string())
+            .add_attribute("refunded", refunded)
+            .add_attribute("action", "execute_reset"))
     }
 
-    pub fn execute_register(
+    pub fn execute_set_paused(
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        car_addrs: Vec<Addr>,
+        paused: bool,
     ) -> Result<Response, ContractError> {
-        let owner = OWNER.load(deps.storage)?;
+        assert_owner(deps.storage, &info.sender)?;
 
-        if info.sender.to_string() != owner {
-            return Err(ContractError::Unauthorized {});
-        }
+        PAUSED.save(deps.storage, &paused)?;
 
-        let mut game_state = GAME_STATE.load(deps.storage)?;
+        Ok(Response::new()
+            .add_attribute("paused", paused.to_string())
+            .add_attribute("action", "set_paused"))
+    }
 
-        if game_state.total_cars() == game_state.config.num_players {
-            return Err(ContractError::LimitPlayers {});
-        }
+    pub fn execute_abort(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        reason: String,
+    ) -> Result<Response, ContractError> {
+        assert_owner_or_operator(deps.storage, &info.sender)?;
+        assert_not_in_turn(deps.storage)?;
 
-        game_state.register(car_addrs.clone());
-        game_state.state = State::Active;
+        let mut game_state = GAME_STATE.load(deps.storage)?;
+        if game_state.state != State::Active {
+            return Err(ContractError::GameNotActive);
+        }
 
+        game_state.state = State::Cancelled {
+            reason: reason.clone(),
+        };
         GAME_STATE.save(deps.storage, &game_state)?;
 
-        for car_addr in car_addrs.clone() {
-            ALL_CAR_DATA.save(deps.storage, car_addr.clone(), &CarData::at_start(car_addr))?;
-        }
+        let (refunded, refund_msgs) = refund_crank_pool(deps.storage, &game_state)?;
 
         Ok(Response::new()
-            .add_attribute(
-                "cars",
-                car_addrs
-                    .into_iter()
-                    .map(|c| c.to_string())
-                    .collect::<Vec<String>>()
-                    .join("|"),
-            )
-            .add_attribute("action", "register"))
+            .add_messages(refund_msgs)
+            .add_attribute("reason", reason)
+            .add_attribute("refunded", refunded)
+            .add_attribute("action", "abort"))
     }
 
-    pub fn execute_play(
+    // Refund what is left in the crank pool of the race to its funders, pro rata of their
+    // deposits
+    fn refund_crank_pool(
+        storage: &mut dyn Storage,
+        game_state: &GameState,
+    ) -> Result<(Uint128, Vec<BankMsg>), ContractError> {
+        let game_id = game_state.game_id;
+        let pool = CRANK_POOL.may_load(storage, game_id)?.unwrap_or_default();
+        let deposits = CRANK_DEPOSITS
+            .prefix(game_id)
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(Addr, Uint128)>>>()?;
+        let total_deposits = deposits
+            .iter()
+            .fold(Uint128::zero(), |total, (_, deposit)| total + deposit);
+
+        let mut refund_msgs = vec![];
+        if let Some(reward) = &game_state.config.crank_reward {
+            let mut left = pool;
+            for (i, (funder, deposit)) in deposits.iter().enumerate() {
+                let refund = if i == deposits.len() - 1 {
+                    left
+                } else {
+                    pool.multiply_ratio(*deposit, total_deposits)
+                };
+                left -= refund;
+
+                if !refund.is_zero() {
+                    refund_msgs.push(BankMsg::Send {
+                        to_address: funder.to_string(),
+                        amount: coins(refund.u128(), reward.denom.clone()),
+                    });
+                }
+            }
+        }
+
+        CRANK_POOL.remove(storage, game_id);
+        for (funder, _) in deposits {
+            CRANK_DEPOSITS.remove(storage, (game_id, funder));
+        }
+        Ok((pool, refund_msgs))
+    }
+
+    pub fn execute_propose_owner(
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        turns_to_play: u64,
+        new_owner: String,
     ) -> Result<Response, ContractError> {
-        let owner = OWNER.load(deps.storage)?;
+        assert_owner(deps.storage, &info.sender)?;
 
-        if info.sender.to_string() != owner {
-            return Err(ContractError::Unauthorized {});
-        }
+        let new_owner = deps.api.addr_validate(&new_owner)?;
+        PENDING_OWNER.save(deps.storage, &new_owner)?;
 
-        let mut turns = turns_to_play;
+        Ok(Response::new()
+            .add_attribute("pending_owner", new_owner)
+            .add_attribute("action", "propose_owner"))
+    }
 
-        let mut out_msgs = Vec::<CosmosMsg>::new();
+    pub fn execute_accept_ownership(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let pending_owner = PENDING_OWNER
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingOwner)?;
 
-        while turns != 0 {
-            let state = GAME_STATE.load(deps.storage)?;
+        if info.sender != pending_owner {
+            return Err(ContractError::Unauthorized {});
+        }
 
-            if !state.can_play() {
-                return Err(ContractError::NotEnoughPlayers);
-            }
+        OWNER.save(deps.storage, &pending_owner)?;
+        PENDING_OWNER.remove(deps.storage);
 
-            let all_cars = state.all_cars.clone();
-            let current_turn = state.turns;
+        Ok(Response::new()
+            .add_attribute("owner", pending_owner)
+            .add_attribute("action", "accept_ownership"))
+    }
 
-            let your_car_index = current_turn % state.config.num_players;
+    pub fn execute_add_operator(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
 
-            let current_turn_car = all_cars[your_car_index as usize].clone();
+        let operator = deps.api.addr_validate(&operator)?;
+        OPERATORS.save(deps.storage, operator.clone(), &true)?;
 
-            // TODO: Pack msg and send to car contract for running their turn
-            // add_message || add_submessage
-            out_msgs.push(
-                WasmMsg::Execute {
-                    contract_addr: current_turn_car.into(),
-                    msg: to_binary(&CarExecuteMsg::TakeTurn {})?,
-                    funds: vec![],
-                }
-                .into(),
-            );
+        Ok(Response::new()
+            .add_attribute("operator", operator)
+            .add_attribute("action", "add_operator"))
+    }
 
-            let mut bananas = get_bananas_sorted_by_y(&state);
+    pub fn execute_remove_operator(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
 
-            for i in 0..state.config.num_players {
-                let car_addr = all_cars[i as usize].clone();
-                let mut car_data = ALL_CAR_DATA.load(deps.storage, car_addr)?;
-                if car_data.shield > 0 {
-                    car_data.shield -= 1;
-                }
+        let operator = deps.api.addr_validate(&operator)?;
+        OPERATORS.remove(deps.storage, operator.clone());
 
-                let len = bananas.len();
-                let car_position = car_data.y;
-                let mut car_target_position = car_position + car_data.speed;
+        Ok(Response::new()
+            .add_attribute("operator", operator)
+            .add_attribute("action", "remove_operator"))
+    }
 
-                for banana_idx in 0..len {
-                    let banana_pos = bananas[banana_idx];
+    pub fn execute_commit_seed(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        commitment: String,
+    ) -> Result<Response, ContractError> {
+        let game_state = GAME_STATE.load(deps.storage)?;
+        assert_seed_open(deps.storage, &game_state, &info.sender)?;
 
-                    if car_position >= banana_pos {
-                        continue;
-                    }
+        // Commits are closed once the first secret is revealed
+        if !SEED_REVEALS.is_empty(deps.storage) {
+            return Err(ContractError::SeedClosed);
+        }
 
-                    if car_target_position >= banana_pos {
-                        car_target_position = banana_pos;
-                        car_data.speed = car_data.speed * state.config.banana_speed_modifier;
-                        bananas[banana_idx] = bananas[len - 1].clone();
-                        bananas.pop();
-                        bananas = get_bananas_sorted_by_y(&state);
-                    }
-                    break;
-                }
-                car_data.y = car_target_position;
-                if car_data.y >= state.config.target_distance {
-                    return Ok(Response::new()
-                        .add_attribute("action", "play")
-                        .add_attribute("winner", car_data.addr.into_string()));
-                }
-            }
+        if SEED_COMMITS.has(deps.storage, info.sender.clone()) {
+            return Err(ContractError::AlreadyCommitted);
+        }
 
-            turns -= 1;
+        let commitment = commitment.to_lowercase();
+        if hex::decode(&commitment).map_or(true, |hash| hash.len() != 32) {
+            return Err(ContractError::InvalidCommitment);
         }
 
+        SEED_COMMITS.save(deps.storage, info.sender.clone(), &commitment)?;
+
         Ok(Response::new()
-            .add_attribute("action", "play")
-            .add_messages(out_msgs))
+            .add_attribute("sender", info.sender)
+            .add_attribute("commitment", commitment)
+            .add_attribute("action", "commit_seed"))
     }
 
-    pub fn execute_buy_shell(
+    pub fn execute_reveal_seed(
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        amount: u64,
+        secret: String,
     ) -> Result<Response, ContractError> {
-        if amount == 0 {
-            return Err(ContractError::ZeroAmount);
+        let mut game_state = GAME_STATE.load(deps.storage)?;
+        assert_seed_open(deps.storage, &game_state, &info.sender)?;
+
+        let commitment = SEED_COMMITS
+            .may_load(deps.storage, info.sender.clone())?
+            .ok_or(ContractError::NoCommitment)?;
+        if sha256_hex(secret.as_bytes()) != commitment {
+            return Err(ContractError::InvalidSecret);
         }
 
-        let mut state = GAME_STATE.load(deps.storage)?;
-        let sender = info.sender;
+        SEED_REVEALS.save(deps.storage, info.sender.clone(), &secret)?;
 
-        let sold = ACTION_SOLD.load(deps.storage, &ActionType::Shell.to_string())?;
-        let cost = get_shell_cost(&state, amount, sold.clone());
+        // The cars reveal first, the seed is fixed when the owner reveals
+        let owner = OWNER.load(deps.storage)?;
+        if info.sender != owner {
+            return Ok(Response::new()
+                .add_attribute("sender", info.sender)
+                .add_attribute("action", "reveal_seed"));
+        }
 
-        let sold_updated = sold + amount;
+        // Owner first, then the cars that have revealed in seat order
+        let mut seed_reveals = vec![SeedReveal {
+            addr: owner,
+            secret,
+        }];
+        for car_addr in game_state.all_cars.iter() {
+            if let Some(secret) = SEED_REVEALS.may_load(deps.storage, car_addr.clone())? {
+                seed_reveals.push(SeedReveal {
+                    addr: car_addr.clone(),
+                    secret,
+                });
+            }
+        }
 
-        ACTION_SOLD.save(deps.storage, &ActionType::Shell.to_string(), &sold_updated)?;
+        let seed = get_turn_order_seed(&seed_reveals);
+        game_state.seed = Some(seed.clone());
+        game_state.seed_reveals = seed_reveals;
+        GAME_STATE.save(deps.storage, &game_state)?;
 
-        let mut car_data = ALL_CAR_DATA.load(deps.storage, sender.clone())?;
-        car_data.balance -= cost;
+        Ok(Response::new()
+            .add_attribute("sender", info.sender)
+            .add_attribute("seed", seed)
+            .add_attribute("action", "reveal_seed"))
+    }
 
-        let y = car_data.y;
+    // The seed can only be contributed by the owner and the cars before the first turn
+    fn assert_seed_open(
+        storage: &dyn Storage,
+        game_state: &GameState,
+        sender: &Addr,
+    ) -> Result<(), ContractError> {
+        if !game_state.config.shuffle_turn_order {
+            return Err(ContractError::ShuffleDisabled);
+        }
 
-        let all_cars = state.all_cars.clone();
+        if game_state.state != State::Active || game_state.turns != 0 || game_state.seed.is_some() {
+            return Err(ContractError::SeedClosed);
+        }
 
-        // Used to determine who to shell.
-        let mut closest_car = CarData::empty();
-        let mut dis_from_closest_car = u64::MAX;
+        if !game_state.all_cars.contains(sender) {
+            assert_owner(storage, sender)?;
+        }
+        Ok(())
+    }
 
-        for i in 0..state.config.num_players {
-            let next_car = ALL_CAR_DATA.load(deps.storage, all_cars[i as usize].clone())?;
+    pub fn execute_update_config(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        config: Config,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
 
-            // If the car is behind or on us, skip it
-            if next_car.y <= y {
-                continue;
-            }
+        set_config(deps.storage, config, None)?;
 
-            // Measure the distance from the car to us
-            let dis_from_next_car = next_car.y - y;
+        Ok(Response::new().add_attribute("action", "update_config"))
+    }
 
-            // If this car is closer than all other cars we've
-            // looked at so far, we'll make it than closest one.
-            if dis_from_next_car < dis_from_closest_car {
-                closest_car = next_car;
-                dis_from_closest_car = dis_from_next_car
-            }
+    pub fn execute_save_preset(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        name: String,
+        config: Config,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
+
+        if name.is_empty() {
+            return Err(ContractError::InvalidPresetName);
         }
+        assert_valid_config(&config)?;
+        PRESETS.save(deps.storage, &name, &config)?;
 
-        // Check for banana collisions
-        for i in 0..state.bananas.len() {
-            // Skip bananas that are behind or on us
-            if state.bananas[i] <= y {
-                continue;
-            }
+        Ok(Response::new()
+            .add_attribute("preset", name)
+            .add_attribute("action", "save_preset"))
+    }
 
-            // Check if the closest car is closer than the closest banana
-            // If a banana is on top of the colest car, the banana is hit
-            if dis_from_closest_car != u64::MAX && state.bananas[i] > y + dis_from_closest_car {
-                break;
-            }
+    pub fn execute_delete_preset(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        name: String,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.storage, &info.sender)?;
 
-            // Remove the banana by swapping it with the last and decreasing the size
-            state.bananas[i] = state.bananas[state.bananas.len() - 1].clone();
-            state.bananas.pop();
-
-            // Sort the bananas
-            let sorted_bananas = get_bananas_sorted_by_y(&state);
-            state.bananas = sorted_bananas;
-
-            // Banana was closer or at the same position as the closestCar
-            closest_car = CarData::empty();
-            break;
-        }
-
-        // If there is a closest car, shell it.
-        if closest_car.addr.clone().into_string() != "" {
-            if closest_car.shield == 0 && closest_car.speed > state.config.post_sell_speed {
-                closest_car.speed = state.config.post_sell_speed;
-                return Ok(Response::new()
-                    .add_attribute("turns", state.turns.clone().to_string())
-                    .add_attribute("sender", sender.clone().to_string())
-                    .add_attribute("shelled", closest_car.clone().addr.to_string())
-                    .add_attribute("amount", amount.to_string())
-                    .add_attribute("action", "shelled"));
-            }
+        if !PRESETS.has(deps.storage, &name) {
+            return Err(ContractError::PresetNotFound { name });
         }
+        PRESETS.remove(deps.storage, &name);
 
-        // No car has shelled
         Ok(Response::new()
-            .add_attribute("sender", sender.to_string())
-            .add_attribute("turns", state.turns.clone().to_string())
-            .add_attribute("action", "buy_shell"))
+            .add_attribute("preset", name)
+            .add_attribute("action", "delete_preset"))
     }
 
-    pub fn execute_buy_accelerate(
+    // Presets are approved by the owner, operators can pick one for the next race
+    pub fn execute_use_preset(
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        amount: u64,
+        name: String,
     ) -> Result<Response, ContractError> {
-        let state = GAME_STATE.load(deps.storage)?;
-        let sold = ACTION_SOLD.load(deps.storage, &ActionType::Accelerate.to_string())?;
+        assert_owner_or_operator(deps.storage, &info.sender)?;
 
-        // Get the cost of the acceleration
-        let cost = get_accel_cost(&state, amount, sold.clone());
+        let config = PRESETS
+            .may_load(deps.storage, &name)?
+            .ok_or_else(|| ContractError::PresetNotFound { name: name.clone() })?;
+        set_config(deps.storage, config, Some(name.clone()))?;
+
+        Ok(Response::new()
+            .add_attribute("preset", name)
+            .add_attribute("action", "use_preset"))
+    }
 
-        // Increase amount of acceleration sold
-        let sold_updated = sold + amount;
-        ACTION_SOLD.save(deps.storage, &ActionType::Shell.to_string(), &sold_updated)?;
+    // Config of the next race, from a preset or not
+    fn set_config(
+        storage: &mut dyn Storage,
+        config: Config,
+        preset: Option<String>,
+    ) -> Result<(), ContractError> {
+        let mut game_state = GAME_STATE.load(storage)?;
+        if game_state.state == State::Active {
+            return Err(ContractError::GameInProgress);
+        }
 
-        let sender = info.sender;
+        assert_valid_config(&config)?;
 
-        let mut sender_car = ALL_CAR_DATA.load(deps.storage, sender.clone())?;
-        sender_car.balance -= cost;
-        sender_car.speed += amount;
+        // The funded pool has to keep paying in its denom
+        let pool = CRANK_POOL
+            .may_load(storage, game_state.game_id)?
+            .unwrap_or_default();
+        let denom = |reward: &Option<Coin>| reward.as_ref().map(|r| r.denom.clone());
+        if !pool.is_zero() && denom(&config.crank_reward) != denom(&game_state.config.crank_reward)
+        {
+            return Err(ContractError::CrankPoolNotEmpty);
+        }
 
-        Ok(Response::new()
-            .add_attribute("turns", state.turns.to_string())
-            .add_attribute("sender_car", sender.to_string())
-            .add_attribute("amount", amount.to_string())
-            .add_attribute("cost", cost.to_string())
-            .add_attribute("action", "buy_accelerate"))
+        game_state.config = config;
+        game_state.preset = preset;
+        GAME_STATE.save(storage, &game_state)?;
+        Ok(())
     }
 
-    pub fn execute_buy_banana(
+    pub fn execute_register(
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
+        car_addrs: Vec<Addr>,
     ) -> Result<Response, ContractError> {
-        let mut state = GAME_STATE.load(deps.storage)?;
-        let mut sender_car = ALL_CAR_DATA.load(deps.storage, info.sender.clone())?;
+        assert_owner_or_operator(deps.storage, &info.sender)?;
+        assert_not_paused(deps.storage)?;
+        assert_not_in_turn(deps.storage)?;
 
-        if state.bananas.len() > 0 && state.bananas[state.bananas.len() - 1] == sender_car.y {
-            return Ok(Response::new()
-                .add_attribute("turns", state.turns.clone().to_string())
-                .add_attribute("sender_car", info.sender.clone().to_string())
-                .add_attribute("action", "buy_banana"));
-        }
+        let mut game_state = GAME_STATE.load(deps.storage)?;
 
-        let sold = ACTION_SOLD.load(deps.storage, &ActionType::Banana.to_string())?;
-        let cost = get_banana_cost(&state, sold.clone());
+        if game_state.total_cars() != 0 {
+            return Err(ContractError::LimitPlayers {});
+        }
 
-        let sold_updated = sold + 1;
+        // Each car takes a seat in registration order
+        if car_addrs.len() as u64 != game_state.config.num_players {
+            return Err(ContractError::InvalidNumPlayers {
+                num_players: car_addrs.len() as u64,
+            });
+        }
 
-        ACTION_SOLD.save(deps.storage, &ActionType::Banana.to_string(), &sold_updated)?;
+        for (i, car_addr) in car_addrs.iter().enumerate() {
+            deps.api.addr_validate(car_addr.as_str())?;
+            if car_addrs[..i].contains(car_addr) {
+                return Err(ContractError::DuplicateCar {
+                    car: car_addr.to_string(),
+                });
+            }
+        }
 
-        sender_car.balance -= cost;
+        game_state.register(car_addrs.clone());
+        game_state.state = State::Active;
 
-        let y = sender_car.y;
+        GAME_STATE.save(deps.storage, &game_state)?;
 
-        state.bananas.push(y);
+        for car_addr in car_addrs.clone() {
+            ALL_CAR_DATA.save(
+                deps.storage,
+                car_addr.clone(),
+                &CarData::at_start(car_addr, game_state.config.init_balance),
+            )?;
+        }
 
         Ok(Response::new()
-            .add_attribute("turns", state.turns.clone().to_string())
-            .add_attribute("sender_car", info.sender.clone().to_string())
-            .add_attribute("cost", cost.to_string())
-            .add_attribute("y", y.to_string())
-            .add_attribute("action", "buy_banana"))
+            .add_attribute(
+                "cars",
+                car_addrs
+                    .into_iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<String>>()
+                    .join("|"),
+            )
+            .add_attribute("action", "register"))
     }
 
-    pub fn execute_buy_shield(
+    pub fn execute_play(
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        amount: u64,
+        turns_to_play: u64,
     ) -> Result<Response, ContractError> {
-        if amount == 0 {
-            return Err(ContractError::ZeroAmount);
+        assert_owner_or_operator(deps.storage, &info.sender)?;
+        assert_not_paused(deps.storage)?;
+        assert_not_in_turn(deps.storage)?;
+
+        play_turns(deps, turns_to_play, None)
+    }
+
+    pub fn execute_crank(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        turns: u64,
+    ) -> Result<Response, ContractError> {
+        assert_not_paused(deps.storage)?;
+        assert_not_in_turn(deps.storage)?;
+
+        let mut state = GAME_STATE.load(deps.storage)?;
+        let config = state.config.clone();
+
+        if !config.crank_enabled {
+            return Err(ContractError::CrankDisabled);
         }
 
-        let state = GAME_STATE.load(deps.storage)?;
-        let mut sender_car = ALL_CAR_DATA.load(deps.storage, info.sender.clone())?;
-        let sold = ACTION_SOLD.load(deps.storage, &ActionType::Shield.to_string())?;
-        let cost = get_shield_cost(&state, amount, sold.clone());
+        if state.state != State::Active {
+            return Err(ContractError::GameNotActive);
+        }
 
-        let sold_updated = sold + amount;
+        if turns == 0 {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        if turns > config.crank_max_turns {
+            return Err(ContractError::CrankTurnsExceeded {
+                max: config.crank_max_turns,
+            });
+        }
 
-        ACTION_SOLD.save(deps.storage, &ActionType::Shield.to_string(), &sold_updated)?;
-        sender_car.balance -= cost;
+        let next_height = state.last_crank_height + config.crank_min_blocks;
+        if state.last_crank_height != 0 && env.block.height < next_height {
+            return Err(ContractError::CrankTooEarly { next_height });
+        }
 
-        sender_car.shield += 1 + amount;
+        state.last_crank_height = env.block.height;
+        GAME_STATE.save(deps.storage, &state)?;
 
-        Ok(Response::new()
-            .add_attribute("sender_car", info.sender.clone().to_string())
-            .add_attribute("amount", amount.to_string())
-            .add_attribute("turns", state.turns.clone().to_string())
-            .add_attribute("cost", cost.to_string())
-            .add_attribute("action", "buy_shield"))
+        // The cranker is rewarded once the turns are played
+        Ok(play_turns(deps, turns, Some(info.sender.clone()))?
+            .add_attribute("cranker", info.sender))
     }
 
-    pub fn execute_buy_super_shell(
+    pub fn execute_fund_crank_pool(
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        amount: u64,
     ) -> Result<Response, ContractError> {
-        if amount == 0 {
+        let state = GAME_STATE.load(deps.storage)?;
+        if matches!(state.state, State::Done | State::Cancelled { .. }) {
+            return Err(ContractError::RaceOver);
+        }
+        let reward = state
+            .config
+            .crank_reward
+            .ok_or(ContractError::CrankDisabled)?;
+
+        if info.funds.len() != 1 || info.funds[0].denom != reward.denom {
+            return Err(ContractError::InvalidFunds {
+                denom: reward.denom,
+            });
+        }
+        let amount = info.funds[0].amount;
+        if amount.is_zero() {
             return Err(ContractError::ZeroAmount);
         }
 
-        let state = GAME_STATE.load(deps.storage)?;
-        let mut sender_car = ALL_CAR_DATA.load(deps.storage, info.sender.clone())?;
-        let sold = ACTION_SOLD.load(deps.storage, &ActionType::SuperShell.to_string())?;
-        let cost = get_super_shell_cost(&state, amount, sold.clone());
-
-        let sold_updated = sold + amount;
-
-        ACTION_SOLD.save(
+        // The pool only rewards the crankers of this race
+        let pool = CRANK_POOL
+            .may_load(deps.storage, state.game_id)?
+            .unwrap_or_default()
+            + amount;
+        CRANK_POOL.save(deps.storage, state.game_id, &pool)?;
+        CRANK_DEPOSITS.update(
             deps.storage,
-            &ActionType::SuperShell.to_string(),
-            &sold_updated,
+            (state.game_id, info.sender.clone()),
+            |deposit| StdResult::Ok(deposit.unwrap_or_default() + amount),
         )?;
-        sender_car.balance -= cost;
 
-        let y = sender_car.y;
+        Ok(Response::new()
+            .add_attribute("funder", info.sender)
+            .add_attribute("amount", amount)
+            .add_attribute("pool", pool)
+            .add_attribute("action", "fund_crank_pool"))
+    }
+
+    // Start playing `turns_to_play` turns. Each turn sends TakeTurn to the current car as a
+    // sub message, the cars are moved and the next turn is started when it replies.
+    fn play_turns(
+        deps: DepsMut,
+        turns_to_play: u64,
+        cranker: Option<Addr>,
+    ) -> Result<Response, ContractError> {
+        if turns_to_play == 0 {
+            return Err(ContractError::ZeroAmount);
+        }
 
-        let all_cars = state.all_cars.clone();
-        for i in 0..state.config.num_players {
-            let mut next_car = ALL_CAR_DATA.load(deps.storage, all_cars[i as usize].clone())?;
-            if next_car.y <= y {
-                continue;
-            }
+        let state = GAME_STATE.load(deps.storage)?;
 
-            if next_car.speed > state.config.post_sell_speed {
-                next_car.speed = state.config.post_sell_speed;
-                return Ok(Response::new()
-                    .add_attribute("cost", cost.to_string())
-                    .add_attribute("turns", state.turns.clone().to_string())
-                    .add_attribute("action", "shelled"));
-            }
+        if state.state != State::Active {
+            return Err(ContractError::GameNotActive);
+        }
+
+        if !state.can_play() {
+            return Err(ContractError::NotEnoughPlayers);
+        }
+
+        if state.config.shuffle_turn_order && state.seed.is_none() {
+            return Err(ContractError::SeedNotRevealed);
         }
 
+        PLAY_CONTEXT.save(
+            deps.storage,
+            &PlayContext {
+                turns_left: turns_to_play,
+                turns_played: 0,
+                cranker,
+            },
+        )?;
+
         Ok(Response::new()
-            .add_attribute("turns", state.turns.clone().to_string())
-            .add_attribute("sender_car", info.sender.clone().to_string())
-            .add_attribute("action", "buy_super_shell"))
+            .add_submessage(take_turn_msg(deps.storage, &state)?)
+            .add_attribute("turns_to_play", turns_to_play.to_string())
+            .add_attribute("action", "play"))
     }
-}
 
-pub fn get_cars_sorted_by_y(deps: Deps, state: &GameState) -> Vec<Addr> {
-    let mut cars = state.all_cars.clone();
-
-    for i in 0..state.config.num_players {
-        for j in (i + 1)..state.config.num_players {
-            let car_data_result_j =
-                ALL_CAR_DATA.load(deps.storage, state.all_cars[j as usize].clone());
-            if car_data_result_j.is_err() {
-                return vec![];
-            }
-            let car_data_j = car_data_result_j.unwrap();
+    pub fn execute_buy(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        action: String,
+        amount: u64,
+    ) -> Result<Response, ContractError> {
+        assert_not_paused(deps.storage)?;
 
-            let car_data_result_i =
-                ALL_CAR_DATA.load(deps.storage, state.all_cars[i as usize].clone());
-            if car_data_result_i.is_err() {
-                return vec![];
-            }
-            let car_data_i = car_data_result_i.unwrap();
+        let mut state = GAME_STATE.load(deps.storage)?;
+        if state.state != State::Active {
+            return Err(ContractError::GameNotActive);
+        }
 
-            if car_data_j.y > car_data_i.y {
-                let temp = cars[i as usize].clone();
-                cars[i as usize] = cars[j as usize].clone();
-                cars[j as usize] = temp
+        // Actions are only bought by a car during its turn, not by a car it calls
+        let sender = info.sender;
+        match TURN_LOCK.may_load(deps.storage)? {
+            Some(car) if car == sender => {}
+            Some(car) => {
+                return Err(ContractError::NotCarTurn {
+                    car: car.to_string(),
+                })
             }
+            None => return Err(ContractError::NoTurnInProgress),
         }
-    }
-
-    cars
-}
 
-pub fn get_all_car_data(deps: Deps, state: &GameState) -> Vec<CarData> {
-    let mut results = Vec::new();
+        let mut race = load_race(deps.storage, &state)?;
 
-    let sorted_cars = get_cars_sorted_by_y(deps, state);
+        let outcome = race.apply_action(sender.as_str(), &action, amount)?;
+        save_race(deps.storage, &mut state, &race)?;
+        GAME_STATE.save(deps.storage, &state)?;
 
-    for i in 0..(state.config.num_players) {
-        let car_addr = sorted_cars[i as usize].clone();
+        let response = Response::new()
+            .add_attribute("turns", state.turns.to_string())
+            .add_attribute("sender_car", sender.to_string())
+            .add_attribute("bought", action.clone())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("cost", outcome.cost.to_string());
 
-        let car_data_result = ALL_CAR_DATA.load(deps.storage, car_addr);
-        if car_data_result.is_err() {
-            return vec![];
+        if !outcome.shelled.is_empty() {
+            return Ok(response
+                .add_attribute("shelled", outcome.shelled.join("|"))
+                .add_attribute("action", "shelled"));
         }
-        let car_data = car_data_result.unwrap();
-        results.push(car_data);
-    }
 
-    results
+        Ok(response.add_attribute("action", format!("buy_{}", action)))
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetAllCarData => to_binary(&query::get_all_car_data(deps)?),
-        QueryMsg::GetOwner => to_binary(&query::get_owner(deps)?),
-        QueryMsg::GetGameState => to_binary(&query::get_game_state(deps)?),
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        TAKE_TURN_REPLY_ID => reply::take_turn_reply(deps, env, msg),
+        id => Err(ContractError::UnknownReplyId { id }),
     }
 }
 
-pub mod query {
-    use std::vec;
-
-    use cosmwasm_std::{Addr, Deps, Order, StdResult};
+pub mod reply {
+    use cosmwasm_std::{
+        coins, BankMsg, DepsMut, Env, Reply, Response, StdError, Storage, SubMsgResult, Uint128,
+    };
 
     use crate::{
-        msg::{AllCarDataReponse, GameStateResponse, OwnerResponse},
-        state::{CarData, ALL_CAR_DATA, GAME_STATE, OWNER},
+        helpers::{load_race, save_race, take_turn_msg},
+        state::{GameState, PlayContext, State, CRANK_POOL, GAME_STATE, PLAY_CONTEXT, TURN_LOCK},
+        ContractError,
+    };
+
+    pub fn take_turn_reply(
+        deps: DepsMut,
+        _env: Env,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        TURN_LOCK.remove(deps.storage);
+        let mut state = GAME_STATE.load(deps.storage)?;
+        let mut context = PLAY_CONTEXT.load(deps.storage)?;
+
+        let mut res = Response::new()
+            .add_attribute("turns", state.turns.to_string())
+            .add_attribute("car", state.current_turn_car())
+            .add_attribute("action", "take_turn");
+
+        // A car failing its turn does not stop the race, it only loses the turn
+        if let SubMsgResult::Err(err) = msg.result {
+            res = res.add_attribute("car_error", err);
+        }
+
+        let mut race = load_race(deps.storage, &state)?;
+        if let Some(winner) = race.advance_turn() {
+            state.state = State::Done;
+            res = res.add_attribute("winner", winner);
+        }
+        save_race(deps.storage, &mut state, &race)?;
+        GAME_STATE.save(deps.storage, &state)?;
+
+        context.turns_left -= 1;
+        context.turns_played += 1;
+
+        if state.state == State::Active && context.turns_left > 0 {
+            PLAY_CONTEXT.save(deps.storage, &context)?;
+            return Ok(res.add_submessage(take_turn_msg(deps.storage, &state)?));
+        }
+
+        PLAY_CONTEXT.remove(deps.storage);
+
+        if let Some(msg) = crank_reward_msg(deps.storage, &state, &context)? {
+            res = res.add_message(msg);
+        }
+
+        Ok(res.add_attribute("turns_played", context.turns_played.to_string()))
+    }
+
+    // Pay the cranker for each turn played, as long as the pool can afford it
+    fn crank_reward_msg(
+        storage: &mut dyn Storage,
+        state: &GameState,
+        context: &PlayContext,
+    ) -> Result<Option<BankMsg>, ContractError> {
+        let (Some(cranker), Some(reward)) = (&context.cranker, &state.config.crank_reward) else {
+            return Ok(None);
+        };
+
+        let pool = CRANK_POOL
+            .may_load(storage, state.game_id)?
+            .unwrap_or_default();
+        let amount = reward
+            .amount
+            .checked_mul(Uint128::from(context.turns_played))
+            .map_err(StdError::overflow)?
+            .min(pool);
+
+        if amount.is_zero() {
+            return Ok(None);
+        }
+
+        CRANK_POOL.save(storage, state.game_id, &(pool - amount))?;
+        Ok(Some(BankMsg::Send {
+            to_address: cranker.to_string(),
+            amount: coins(amount.u128(), reward.denom.clone()),
+        }))
+    }
+}
+
+pub fn get_cars_sorted_by_y(deps: Deps, state: &GameState) -> Vec<Addr> {
+    get_all_car_data(deps, state)
+        .into_iter()
+        .map(|car_data| car_data.addr)
+        .collect()
+}
+
+// All cars data sorted from the first to the last car, cars at the same position keep
+// their seat order
+pub fn get_all_car_data(deps: Deps, state: &GameState) -> Vec<CarData> {
+    match load_race(deps.storage, state) {
+        Ok(race) => race
+            .standings()
+            .into_iter()
+            .map(CarData::from_car)
+            .collect(),
+        Err(_) => vec![],
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetAllCarData => to_binary(&query::get_all_car_data(deps)?),
+        QueryMsg::GetOwner => to_binary(&query::get_owner(deps)?),
+        QueryMsg::GetOperators => to_binary(&query::get_operators(deps)?),
+        QueryMsg::GetGameState => to_binary(&query::get_game_state(deps)?),
+        QueryMsg::GetStandings => to_binary(&query::get_standings(deps)?),
+        QueryMsg::ListActions { car } => to_binary(&query::list_actions(deps, car)?),
+        QueryMsg::GetActionsSold => to_binary(&query::get_actions_sold(deps)?),
+        QueryMsg::GetTurnOrder { round } => to_binary(&query::get_turn_order(deps, round)?),
+        QueryMsg::GetCrankPool => to_binary(&query::get_crank_pool(deps)?),
+        QueryMsg::IsPaused => to_binary(&query::is_paused(deps)?),
+        QueryMsg::GetRaceHistory { game_id } => to_binary(&query::get_race_history(deps, game_id)?),
+        QueryMsg::ListPresets => to_binary(&query::list_presets(deps)?),
+    }
+}
+
+pub mod query {
+    use std::vec;
+
+    use cosmwasm_std::{Addr, Deps, Order, StdResult};
+
+    use crate::{
+        helpers::get_action_cost,
+        msg::{
+            ActionInfo, ActionsResponse, ActionsSoldResponse, AllCarDataReponse, CarActionsSold,
+            CrankPoolResponse, GameStateResponse, OperatorsResponse, OwnerResponse, PausedResponse,
+            Preset, PresetsResponse, StandingsResponse, TurnOrderResponse,
+        },
+        state::{
+            ActionSupply, CarData, Config, RaceRecord, ACTION_SOLD, ALL_CAR_DATA, CAR_ACTION_SOLD,
+            CRANK_POOL, GAME_STATE, OPERATORS, OWNER, PAUSED, PENDING_OWNER, PRESETS, RACE_HISTORY,
+        },
     };
 
     pub fn get_all_car_data(deps: Deps) -> StdResult<AllCarDataReponse> {
@@ -534,7 +933,21 @@ pub mod query {
 
     pub fn get_owner(deps: Deps) -> StdResult<OwnerResponse> {
         let owner = OWNER.may_load(deps.storage)?;
-        Ok(OwnerResponse { owner })
+        let pending_owner = PENDING_OWNER.may_load(deps.storage)?;
+        Ok(OwnerResponse {
+            owner: owner.map(String::from),
+            pending_owner: pending_owner.map(String::from),
+        })
+    }
+
+    pub fn get_operators(deps: Deps) -> StdResult<OperatorsResponse> {
+        let operators: StdResult<Vec<String>> = OPERATORS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .map(|operator| operator.map(String::from))
+            .collect();
+        Ok(OperatorsResponse {
+            operators: operators?,
+        })
     }
 
     pub fn get_game_state(deps: Deps) -> StdResult<GameStateResponse> {
@@ -542,9 +955,127 @@ pub mod query {
         Ok(GameStateResponse {
             turns: game_sate.turns,
             config: game_sate.config,
+            preset: game_sate.preset,
             state: game_sate.state,
         })
     }
+
+    pub fn get_standings(deps: Deps) -> StdResult<StandingsResponse> {
+        let game_state = GAME_STATE.load(deps.storage)?;
+        Ok(StandingsResponse {
+            cars: super::get_all_car_data(deps, &game_state),
+        })
+    }
+
+    pub fn list_actions(deps: Deps, car: Option<Addr>) -> StdResult<ActionsResponse> {
+        let state = GAME_STATE.load(deps.storage)?;
+        // The car only matters when its prices follow its own purchases
+        let car = car.filter(|_| state.config.action_supply == ActionSupply::PerCar);
+        let actions = state
+            .config
+            .actions
+            .iter()
+            .map(|action| {
+                let sold = match &car {
+                    Some(car) => {
+                        CAR_ACTION_SOLD.may_load(deps.storage, (car.clone(), &action.name))?
+                    }
+                    None => ACTION_SOLD.may_load(deps.storage, &action.name)?,
+                }
+                .unwrap_or_default();
+                Ok(ActionInfo {
+                    name: action.name.clone(),
+                    effect: action.effect,
+                    pricing: action.pricing.clone(),
+                    sold,
+                    price: get_action_cost(&action.pricing, state.turns, 1, sold, u64::MAX),
+                })
+            })
+            .collect::<StdResult<Vec<ActionInfo>>>()?;
+        Ok(ActionsResponse { actions })
+    }
+
+    pub fn get_actions_sold(deps: Deps) -> StdResult<ActionsSoldResponse> {
+        let state = GAME_STATE.load(deps.storage)?;
+        let global = ACTION_SOLD
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<(String, u64)>>>()?;
+        let per_car = state
+            .all_cars
+            .iter()
+            .map(|car| {
+                let sold = CAR_ACTION_SOLD
+                    .prefix(car.clone())
+                    .range(deps.storage, None, None, Order::Ascending)
+                    .collect::<StdResult<Vec<(String, u64)>>>()?;
+                Ok(CarActionsSold {
+                    car: car.clone(),
+                    sold,
+                })
+            })
+            .collect::<StdResult<Vec<CarActionsSold>>>()?;
+
+        Ok(ActionsSoldResponse {
+            supply: state.config.action_supply,
+            global,
+            per_car,
+        })
+    }
+
+    pub fn get_turn_order(deps: Deps, round: u64) -> StdResult<TurnOrderResponse> {
+        let game_state = GAME_STATE.load(deps.storage)?;
+        Ok(TurnOrderResponse {
+            round,
+            order: game_state.turn_order(round),
+            seed: game_state.seed,
+        })
+    }
+
+    pub fn is_paused(deps: Deps) -> StdResult<PausedResponse> {
+        let paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+        Ok(PausedResponse { paused })
+    }
+
+    pub fn get_race_history(deps: Deps, game_id: u64) -> StdResult<RaceRecord> {
+        let game_state = GAME_STATE.load(deps.storage)?;
+
+        // The current race is not archived yet
+        if game_id == game_state.game_id {
+            let cars = game_state
+                .all_cars
+                .iter()
+                .map(|car_addr| ALL_CAR_DATA.load(deps.storage, car_addr.clone()))
+                .collect::<StdResult<Vec<CarData>>>()?;
+            return Ok(RaceRecord {
+                game: game_state,
+                cars,
+            });
+        }
+
+        RACE_HISTORY.load(deps.storage, game_id)
+    }
+
+    pub fn list_presets(deps: Deps) -> StdResult<PresetsResponse> {
+        let presets = PRESETS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (name, config): (String, Config) = item?;
+                Ok(Preset { name, config })
+            })
+            .collect::<StdResult<Vec<Preset>>>()?;
+        Ok(PresetsResponse { presets })
+    }
+
+    pub fn get_crank_pool(deps: Deps) -> StdResult<CrankPoolResponse> {
+        let game_state = GAME_STATE.load(deps.storage)?;
+        let pool = CRANK_POOL
+            .may_load(deps.storage, game_state.game_id)?
+            .unwrap_or_default();
+        Ok(CrankPoolResponse {
+            pool,
+            reward: game_state.config.crank_reward,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -552,22 +1083,35 @@ mod tests {
     use std::vec;
 
     use cosmwasm_std::{
-        from_binary,
+        attr, coin, coins, from_binary, from_slice,
         testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-        Addr, Empty, OwnedDeps,
+        Addr, BankMsg, CosmosMsg, Empty, OwnedDeps, Reply, Response, SubMsg, SubMsgResponse,
+        SubMsgResult, Uint128, WasmMsg,
     };
+    use titans_car_sdk::TurnInfo;
+    use titans_engine::pricing::WAD;
 
     use crate::{
         contract::execute,
         contract::instantiate,
         msg::{
-            AllCarDataReponse, ExecuteMsg, GameStateResponse, InstantiateMsg, OwnerResponse,
-            QueryMsg,
+            ActionsResponse, ActionsSoldResponse, AllCarDataReponse, CarActionsSold,
+            CrankPoolResponse, ExecuteMsg, GameStateResponse, InstantiateMsg, OperatorsResponse,
+            OwnerResponse, PausedResponse, PresetsResponse, QueryMsg, StandingsResponse,
+            TurnOrderResponse,
         },
-        state::{State, GAME_STATE},
+        state::{
+            ActionConfig, ActionEffect, ActionSupply, CarData, Config, PricingSpec, RaceRecord,
+            State, ACCELERATE, ALL_CAR_DATA, BANANA, CRANK_DEPOSITS, CRANK_POOL, GAME_STATE, SHELL,
+            SHIELD, SUPER_SHELL, TURN_LOCK,
+        },
+        ContractError,
     };
 
-    use super::{get_all_car_data, get_cars_sorted_by_y, query};
+    use super::{get_all_car_data, get_cars_sorted_by_y, query, reply, TAKE_TURN_REPLY_ID};
+    use crate::helpers::{
+        assert_valid_config, get_turn_order_seed, load_race, sha256_hex, shuffle_seats,
+    };
 
     #[test]
     fn test_instantiate_work() {
@@ -582,7 +1126,7 @@ mod tests {
         let info = mock_info("sender", &[]);
         let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
         println!("len attributes = {:?}", res.attributes.len());
-        assert!(res.attributes.len() != 0);
+        assert!(!res.attributes.is_empty());
     }
 
     fn instantiate_deps() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
@@ -626,8 +1170,8 @@ mod tests {
 
         let owner = "owner";
         let not_owner = "not_owner";
-        let owner_info = mock_info(owner, &vec![]);
-        let not_onwer_info = mock_info(not_owner, &vec![]);
+        let owner_info = mock_info(owner, &[]);
+        let not_onwer_info = mock_info(not_owner, &[]);
 
         let res = execute(deps.as_mut(), mock_env(), owner_info, msg.clone());
         assert!(res.is_ok());
@@ -667,9 +1211,7 @@ mod tests {
 
         let msg = ExecuteMsg::BuyAccelerate { amount: 1 };
 
-        let info = mock_info("car1", &[]);
-
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        let res = buy(&mut deps, "car1", msg);
 
         println!("res: {:?}", res);
 
@@ -682,9 +1224,7 @@ mod tests {
 
         let msg = ExecuteMsg::BuyShell { amount: 1 };
 
-        let info = mock_info("car1", &[]);
-
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        let res = buy(&mut deps, "car1", msg);
 
         assert!(res.is_ok());
     }
@@ -695,9 +1235,7 @@ mod tests {
 
         let msg = ExecuteMsg::BuySuperShell { amount: 1 };
 
-        let info = mock_info("car1", &[]);
-
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        let res = buy(&mut deps, "car1", msg);
 
         assert!(res.is_ok());
     }
@@ -708,9 +1246,7 @@ mod tests {
 
         let msg = ExecuteMsg::BuyShield { amount: 1 };
 
-        let info = mock_info("car1", &[]);
-
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        let res = buy(&mut deps, "car1", msg);
 
         assert!(res.is_ok());
     }
@@ -721,112 +1257,1435 @@ mod tests {
 
         let msg = ExecuteMsg::BuyBanana {};
 
-        let info = mock_info("car1", &[]);
-
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        let res = buy(&mut deps, "car1", msg);
 
         assert!(res.is_ok());
     }
 
     #[test]
-    fn test_query_owner() {
-        let deps = instantiate_deps();
+    fn test_buy_action() {
+        // A flat price of 20 without a decrease
+        let mut config = Config::default();
+        config.actions.push(ActionConfig {
+            name: "nitro".to_string(),
+            pricing: PricingSpec {
+                target_price: 20,
+                per_turn_decrease: 0,
+                sell_per_turn: WAD,
+            },
+            effect: ActionEffect::Accelerate,
+        });
+        let mut deps = register_deps_with_config(config);
+
+        let msg = ExecuteMsg::Buy {
+            action: "nitro".to_string(),
+            amount: 3,
+        };
+        let res = buy(&mut deps, "car1", msg).unwrap();
+        assert!(res.attributes.contains(&attr("action", "buy_nitro")));
+        let car = load_car(&deps, "car1");
+        assert_eq!(car.speed, 3);
+        assert_eq!(car.balance, 17500 - 60);
+
+        let msg = ExecuteMsg::Buy {
+            action: "rocket".to_string(),
+            amount: 1,
+        };
+        let res = buy(&mut deps, "car1", msg);
+        assert!(matches!(res, Err(ContractError::UnknownAction { .. })));
 
-        let msg = QueryMsg::GetOwner;
+        let msg = ExecuteMsg::Buy {
+            action: BANANA.to_string(),
+            amount: 2,
+        };
+        let res = buy(&mut deps, "car1", msg);
+        assert!(matches!(
+            res,
+            Err(ContractError::InvalidActionAmount { amount: 2, .. })
+        ));
+
+        let ActionsResponse { actions } = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ListActions { car: None },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let names: Vec<&str> = actions.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![ACCELERATE, SHELL, SUPER_SHELL, BANANA, SHIELD, "nitro"]
+        );
+        assert_eq!(actions[5].sold, 3);
+        assert_eq!(actions[5].effect, ActionEffect::Accelerate);
+        assert_eq!(actions[0].sold, 0);
+    }
 
-        let res = query(deps.as_ref(), mock_env(), msg);
+    #[test]
+    fn test_actions_sold() {
+        let mut deps = register_deps_with_config(Config {
+            action_supply: ActionSupply::PerCar,
+            ..Config::default()
+        });
+
+        for (car, msg) in [
+            ("car1", ExecuteMsg::BuyAccelerate { amount: 2 }),
+            ("car2", ExecuteMsg::BuyAccelerate { amount: 1 }),
+            ("car2", ExecuteMsg::BuyShell { amount: 1 }),
+        ] {
+            buy(&mut deps, car, msg).unwrap();
+        }
 
-        assert!(res.is_ok());
+        let res: ActionsSoldResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetActionsSold).unwrap())
+                .unwrap();
+        assert_eq!(res.supply, ActionSupply::PerCar);
+        assert_eq!(
+            res.global,
+            vec![(ACCELERATE.to_string(), 3), (SHELL.to_string(), 1)]
+        );
+        assert_eq!(
+            res.per_car,
+            vec![
+                CarActionsSold {
+                    car: Addr::unchecked("car1"),
+                    sold: vec![(ACCELERATE.to_string(), 2)],
+                },
+                CarActionsSold {
+                    car: Addr::unchecked("car2"),
+                    sold: vec![(ACCELERATE.to_string(), 1), (SHELL.to_string(), 1)],
+                },
+                CarActionsSold {
+                    car: Addr::unchecked("car3"),
+                    sold: vec![],
+                },
+            ]
+        );
 
-        let OwnerResponse { owner } = from_binary(&res.unwrap()).unwrap();
+        // The counters start over with the next race
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Reset {},
+        )
+        .unwrap();
+        let res: ActionsSoldResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetActionsSold).unwrap())
+                .unwrap();
+        assert!(res.global.is_empty());
+        assert!(res.per_car.is_empty());
+    }
 
-        println!("res: {:?}", owner.unwrap());
+    #[test]
+    fn test_list_actions_per_car() {
+        let mut deps = register_deps_with_config(Config {
+            action_supply: ActionSupply::PerCar,
+            ..Config::default()
+        });
+        for (car, amount) in [("car1", 6), ("car2", 2), ("car1", 3)] {
+            buy(&mut deps, car, ExecuteMsg::BuyAccelerate { amount }).unwrap();
+        }
+        let list_actions = |car: Option<&str>| {
+            let msg = QueryMsg::ListActions {
+                car: car.map(Addr::unchecked),
+            };
+            let res: ActionsResponse =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            res.actions
+        };
 
-        let deps = register_deps();
+        // A car on chain sees the prices of a car in a race played natively
+        let state = GAME_STATE.load(&deps.storage).unwrap();
+        let race = load_race(&deps.storage, &state).unwrap();
+        for car in ["car1", "car2", "car3"] {
+            let turn = TurnInfo::from_race(&race, car).unwrap();
+            assert_eq!(list_actions(Some(car)), turn.actions);
+        }
+        assert_eq!(list_actions(Some("car1"))[0].sold, 9);
+        assert_eq!(list_actions(Some("car2"))[0].sold, 2);
 
-        let msg = QueryMsg::GetOwner;
+        // Without a car, the sales to all the cars
+        assert_eq!(list_actions(None)[0].sold, 11);
+    }
 
-        let res = query(deps.as_ref(), mock_env(), msg);
+    #[test]
+    fn test_update_config_duplicate_action() {
+        let mut deps = instantiate_deps();
 
-        assert!(res.is_ok());
+        let mut config = Config::default();
+        config
+            .actions
+            .push(ActionConfig::new(SHELL, ActionEffect::SuperShell));
+        let msg = ExecuteMsg::UpdateConfig {
+            config: Box::new(config),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::DuplicateAction { .. })));
+    }
+
+    #[test]
+    fn test_update_config_invalid_pricing() {
+        let mut deps = instantiate_deps();
 
-        let OwnerResponse { owner } = from_binary(&res.unwrap()).unwrap();
+        for (per_turn_decrease, sell_per_turn) in [(WAD, WAD), (0, 0)] {
+            let mut config = Config::default();
+            config.actions[1].pricing.per_turn_decrease = per_turn_decrease;
+            config.actions[1].pricing.sell_per_turn = sell_per_turn;
+            let msg = ExecuteMsg::UpdateConfig {
+                config: Box::new(config),
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+            assert!(matches!(res, Err(ContractError::InvalidPricing { .. })));
+        }
 
-        println!("res: {:?}", owner.unwrap());
+        // Free actions do not need a curve
+        let mut config = Config::default();
+        config.actions[1].pricing = PricingSpec::default();
+        let msg = ExecuteMsg::UpdateConfig {
+            config: Box::new(config),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
     }
 
     #[test]
-    fn test_query_all_car_data() {
-        let deps = instantiate_deps();
-        let msg = QueryMsg::GetAllCarData;
-        let res = query(deps.as_ref(), mock_env(), msg);
-        assert!(res.is_ok());
-        let AllCarDataReponse { all_cars } = from_binary(&res.unwrap()).unwrap();
-        println!("all_cars: {:?}", all_cars);
-        assert!(all_cars.len() == 0);
+    fn test_instantiate_honors_owner() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            owner: "owner".to_owned(),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("deployer", &[]), msg).unwrap();
 
-        let deps = register_deps();
-        let msg = QueryMsg::GetAllCarData {};
-        let res = query(deps.as_ref(), mock_env(), msg);
-        assert!(res.is_ok());
-        let AllCarDataReponse { all_cars } = from_binary(&res.unwrap()).unwrap();
-        println!("all_cars: {:?}", all_cars);
-        assert!(all_cars.len() == 3);
+        let OwnerResponse { owner, .. } =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetOwner).unwrap()).unwrap();
+        assert_eq!(owner, Some("owner".to_string()));
+
+        let msg = ExecuteMsg::Reset {};
+        let res = execute(deps.as_mut(), mock_env(), mock_info("deployer", &[]), msg);
+        assert!(res.is_err());
     }
 
     #[test]
-    fn test_query_game_state() {
-        let deps = instantiate_deps();
-        let msg = QueryMsg::GetGameState;
-        let res = query(deps.as_ref(), mock_env(), msg);
+    fn test_transfer_ownership() {
+        let mut deps = instantiate_deps();
+
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let res = execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::NoPendingOwner)));
+
+        let msg = ExecuteMsg::ProposeOwner {
+            new_owner: "new_owner".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("new_owner", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_err());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
         assert!(res.is_ok());
-        let GameStateResponse {
-            turns,
-            config,
-            state,
-        } = from_binary(&res.unwrap()).unwrap();
-        println!("turns: {:?}", turns);
-        assert!(turns == 0);
-        println!("config: {:?}", config);
-        println!("state: {:?}", state);
-        assert!(state == State::Waiting);
 
-        let deps = register_deps();
-        let msg = QueryMsg::GetGameState;
-        let res = query(deps.as_ref(), mock_env(), msg);
+        let OwnerResponse {
+            owner,
+            pending_owner,
+        } = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetOwner).unwrap()).unwrap();
+        assert_eq!(owner, Some("owner".to_string()));
+        assert_eq!(pending_owner, Some("new_owner".to_string()));
+
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_err());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg);
         assert!(res.is_ok());
-        let GameStateResponse {
-            turns,
-            config,
-            state,
-        } = from_binary(&res.unwrap()).unwrap();
-        println!("turns: {:?}", turns);
-        assert!(turns == 0);
-        println!("config: {:?}", config);
-        println!("state: {:?}", state);
-        assert!(state == State::Active);
-    }
 
-    #[test]
-    fn test_get_cars_sorted_by_y() {
-        let deps = register_deps();
-        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        let OwnerResponse {
+            owner,
+            pending_owner,
+        } = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetOwner).unwrap()).unwrap();
+        assert_eq!(owner, Some("new_owner".to_string()));
+        assert_eq!(pending_owner, None);
 
-        let cars = get_cars_sorted_by_y(deps.as_ref(), &game_state);
-        assert!(cars.len() == 3);
-        println!("cars: {:?}", cars);
+        // The old owner has lost its rights
+        let msg = ExecuteMsg::Reset {};
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_err());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("new_owner", &[]), msg);
+        assert!(res.is_ok());
     }
 
     #[test]
-    fn test_get_all_car_data_and_find_car() {
-        let deps = register_deps();
-        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+    fn test_operators() {
+        let mut deps = instantiate_deps();
 
-        let all_car_data = get_all_car_data(deps.as_ref(), &game_state);
+        let msg = ExecuteMsg::AddOperator {
+            operator: "operator".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("operator", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_err());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(res.is_ok());
 
-        println!("all_car_data: {:?}", all_car_data);
+        let OperatorsResponse { operators } =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetOperators).unwrap())
+                .unwrap();
+        assert_eq!(operators, vec!["operator".to_string()]);
+
+        // Operators can manage races
+        let operator_info = mock_info("operator", &[]);
+        let msg = ExecuteMsg::Register {
+            car_addrs: vec![
+                Addr::unchecked("car1"),
+                Addr::unchecked("car2"),
+                Addr::unchecked("car3"),
+            ],
+        };
+        let res = execute(deps.as_mut(), mock_env(), operator_info.clone(), msg);
+        assert!(res.is_ok());
+        let msg = ExecuteMsg::Reset {};
+        let res = execute(deps.as_mut(), mock_env(), operator_info.clone(), msg);
+        assert!(res.is_ok());
+
+        // But not the config or the ownership
+        let msg = ExecuteMsg::UpdateConfig {
+            config: Box::default(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), operator_info.clone(), msg);
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::ProposeOwner {
+            new_owner: "operator".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), operator_info.clone(), msg);
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::AddOperator {
+            operator: "other".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), operator_info.clone(), msg);
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        let msg = ExecuteMsg::RemoveOperator {
+            operator: "operator".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(res.is_ok());
+        let msg = ExecuteMsg::Reset {};
+        let res = execute(deps.as_mut(), mock_env(), operator_info, msg);
+        assert!(res.is_err());
     }
 
     #[test]
-    fn test_play() {}
+    fn test_update_config() {
+        let mut deps = instantiate_deps();
+
+        let config = Config {
+            target_distance: 500,
+            ..Config::default()
+        };
+        let msg = ExecuteMsg::UpdateConfig {
+            config: Box::new(config.clone()),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_ok());
+
+        // The config is kept after a reset
+        let msg_reset = ExecuteMsg::Reset {};
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg_reset,
+        )
+        .unwrap();
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        assert_eq!(game_state.config, config);
+
+        // The config can not be changed while racing
+        let deps_racing = &mut register_deps();
+        let res = execute(
+            deps_racing.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg,
+        );
+        assert!(matches!(res, Err(ContractError::GameInProgress)));
+    }
+
+    #[test]
+    fn test_presets() {
+        let mut deps = instantiate_deps();
+        let sprint = Config {
+            num_players: 2,
+            target_distance: 300,
+            ..Config::default()
+        };
+        let save_sprint = ExecuteMsg::SavePreset {
+            name: "sprint".to_string(),
+            config: Box::new(sprint.clone()),
+        };
+
+        // Only the owner manages the presets, with valid configs
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("operator", &[]),
+            save_sprint.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::SavePreset {
+            name: "solo".to_string(),
+            config: Box::new(Config {
+                num_players: 1,
+                ..Config::default()
+            }),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InvalidNumPlayers { .. })));
+        let msg = ExecuteMsg::SavePreset {
+            name: "".to_string(),
+            config: Box::default(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InvalidPresetName)));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            save_sprint,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::SavePreset {
+            name: "classic".to_string(),
+            config: Box::default(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let PresetsResponse { presets } =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ListPresets).unwrap()).unwrap();
+        let names: Vec<&str> = presets.iter().map(|preset| preset.name.as_str()).collect();
+        assert_eq!(names, vec!["classic", "sprint"]);
+        assert_eq!(presets[1].config, sprint);
+
+        // Operators can pick a preset for the next race, it is kept after a reset
+        let msg = ExecuteMsg::AddOperator {
+            operator: "operator".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let use_sprint = ExecuteMsg::UsePreset {
+            name: "sprint".to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("operator", &[]),
+            use_sprint.clone(),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Reset {},
+        )
+        .unwrap();
+        let GameStateResponse { config, preset, .. } =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetGameState).unwrap())
+                .unwrap();
+        assert_eq!(config, sprint);
+        assert_eq!(preset, Some("sprint".to_string()));
+
+        // Updating the config directly leaves the preset
+        let msg = ExecuteMsg::UpdateConfig {
+            config: Box::default(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        assert_eq!(game_state.preset, None);
+
+        // Deleted presets can not be used
+        let msg = ExecuteMsg::DeletePreset {
+            name: "sprint".to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("operator", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::PresetNotFound { .. })));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("operator", &[]),
+            use_sprint,
+        );
+        assert!(matches!(res, Err(ContractError::PresetNotFound { .. })));
+
+        // Presets can not change the config while racing
+        let deps_racing = &mut register_deps();
+        let msg = ExecuteMsg::SavePreset {
+            name: "classic".to_string(),
+            config: Box::default(),
+        };
+        execute(
+            deps_racing.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::UsePreset {
+            name: "classic".to_string(),
+        };
+        let res = execute(
+            deps_racing.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg,
+        );
+        assert!(matches!(res, Err(ContractError::GameInProgress)));
+    }
+
+    #[test]
+    fn test_preset_files() {
+        // The presets shared with the simulator and the runner
+        let default: Config = from_slice(include_bytes!("../../../presets/default.json")).unwrap();
+        assert_eq!(default, Config::default());
+
+        let sprint: Config = from_slice(include_bytes!("../../../presets/sprint.json")).unwrap();
+        assert_valid_config(&sprint).unwrap();
+    }
+
+    // Reply to the TakeTurn sub messages as if every car has played its turn,
+    // returns all the messages sent until the turns are played
+    fn reply_turns(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+        res: Response,
+    ) -> Vec<SubMsg> {
+        let mut msgs = vec![];
+        let mut next = Some(res);
+        while let Some(res) = next.take() {
+            for msg in res.messages {
+                if msg.id == TAKE_TURN_REPLY_ID {
+                    let reply_msg = Reply {
+                        id: msg.id,
+                        result: SubMsgResult::Ok(SubMsgResponse {
+                            events: vec![],
+                            data: None,
+                        }),
+                    };
+                    next = Some(reply(deps.as_mut(), mock_env(), reply_msg).unwrap());
+                }
+                msgs.push(msg);
+            }
+        }
+        msgs
+    }
+
+    fn register_deps_with_config(
+        config: Config,
+    ) -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
+        let mut deps = instantiate_deps();
+        let num_players = config.num_players;
+        let msg = ExecuteMsg::UpdateConfig {
+            config: Box::new(config),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Register {
+            car_addrs: (1..=num_players)
+                .map(|i| Addr::unchecked(format!("car{}", i)))
+                .collect(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        deps
+    }
+
+    // Buy as `car` during its turn
+    fn buy(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+        car: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        TURN_LOCK
+            .save(&mut deps.storage, &Addr::unchecked(car))
+            .unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info(car, &[]), msg);
+        TURN_LOCK.remove(&mut deps.storage);
+        res
+    }
+
+    fn set_car(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+        car: &str,
+        y: u64,
+        speed: u64,
+        shield: u64,
+    ) {
+        let addr = Addr::unchecked(car);
+        let mut car_data = ALL_CAR_DATA.load(&deps.storage, addr.clone()).unwrap();
+        car_data.y = y;
+        car_data.speed = speed;
+        car_data.shield = shield;
+        ALL_CAR_DATA
+            .save(&mut deps.storage, addr, &car_data)
+            .unwrap();
+    }
+
+    fn load_car(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, car: &str) -> CarData {
+        ALL_CAR_DATA
+            .load(&deps.storage, Addr::unchecked(car))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_register_num_players() {
+        let mut deps = instantiate_deps();
+
+        for num_players in [1, 9] {
+            let msg = ExecuteMsg::UpdateConfig {
+                config: Box::new(Config {
+                    num_players,
+                    ..Config::default()
+                }),
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+            assert!(matches!(res, Err(ContractError::InvalidNumPlayers { .. })));
+        }
+
+        // The default config is for 3 players
+        let msg = ExecuteMsg::Register {
+            car_addrs: vec![Addr::unchecked("car1"), Addr::unchecked("car2")],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(
+            res,
+            Err(ContractError::InvalidNumPlayers { num_players: 2 })
+        ));
+
+        let msg = ExecuteMsg::Register {
+            car_addrs: vec![
+                Addr::unchecked("car1"),
+                Addr::unchecked("car2"),
+                Addr::unchecked("car1"),
+            ],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::DuplicateCar { .. })));
+
+        for num_players in [2, 8] {
+            let deps = register_deps_with_config(Config {
+                num_players,
+                ..Config::default()
+            });
+            let game_state = GAME_STATE.load(&deps.storage).unwrap();
+            assert_eq!(game_state.total_cars(), num_players);
+            assert_eq!(game_state.state, State::Active);
+        }
+    }
+
+    #[test]
+    fn test_turn_rotation() {
+        for num_players in [2, 5, 8] {
+            let mut deps = register_deps_with_config(Config {
+                num_players,
+                ..Config::default()
+            });
+
+            let msg = ExecuteMsg::Play {
+                turns_to_play: num_players + 1,
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+            let cars: Vec<String> = reply_turns(&mut deps, res)
+                .into_iter()
+                .filter_map(|msg| match msg.msg {
+                    CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => Some(contract_addr),
+                    _ => None,
+                })
+                .collect();
+
+            let mut expected: Vec<String> =
+                (1..=num_players).map(|i| format!("car{}", i)).collect();
+            expected.push("car1".to_string());
+            assert_eq!(cars, expected);
+
+            let game_state = GAME_STATE.load(&deps.storage).unwrap();
+            assert_eq!(game_state.turns, num_players + 1);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_turn_order() {
+        let mut deps = register_deps_with_config(Config {
+            num_players: 4,
+            shuffle_turn_order: true,
+            reshuffle_each_round: true,
+            ..Config::default()
+        });
+
+        let msg = ExecuteMsg::Play { turns_to_play: 1 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::SeedNotRevealed)));
+
+        let commit = |secret: &str| ExecuteMsg::CommitSeed {
+            commitment: sha256_hex(secret.as_bytes()),
+        };
+        let reveal = |secret: &str| ExecuteMsg::RevealSeed {
+            secret: secret.to_string(),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stranger", &[]),
+            commit("stranger secret"),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let msg = ExecuteMsg::CommitSeed {
+            commitment: "not a hash".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("car1", &[]), msg);
+        assert!(matches!(res, Err(ContractError::InvalidCommitment)));
+
+        for (sender, secret) in [("owner", "owner secret"), ("car1", "car1 secret")] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                commit(secret),
+            )
+            .unwrap();
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("car1", &[]),
+            commit("car1 secret"),
+        );
+        assert!(matches!(res, Err(ContractError::AlreadyCommitted)));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("car1", &[]),
+            reveal("wrong secret"),
+        );
+        assert!(matches!(res, Err(ContractError::InvalidSecret)));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("car1", &[]),
+            reveal("car1 secret"),
+        )
+        .unwrap();
+
+        // No more commits once a secret is revealed
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("car2", &[]),
+            commit("car2 secret"),
+        );
+        assert!(matches!(res, Err(ContractError::SeedClosed)));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            reveal("owner secret"),
+        )
+        .unwrap();
+
+        // The seed can be recomputed from the revealed secrets
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        let seed = game_state.seed.clone().unwrap();
+        assert_eq!(seed, get_turn_order_seed(&game_state.seed_reveals));
+        assert_eq!(game_state.seed_reveals.len(), 2);
+        assert_eq!(game_state.seed_reveals[0].addr, Addr::unchecked("owner"));
+
+        let msg = ExecuteMsg::Play { turns_to_play: 8 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        let cars: Vec<Addr> = reply_turns(&mut deps, res)
+            .into_iter()
+            .filter_map(|msg| match msg.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+                    Some(Addr::unchecked(contract_addr))
+                }
+                _ => None,
+            })
+            .collect();
+
+        // Each round is played in the order derived from the seed
+        for round in 0..2 {
+            let msg = QueryMsg::GetTurnOrder { round };
+            let TurnOrderResponse { order, .. } =
+                from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            let seats = shuffle_seats(&seed, round, 4);
+            let expected: Vec<Addr> = seats
+                .into_iter()
+                .map(|seat| Addr::unchecked(format!("car{}", seat + 1)))
+                .collect();
+            assert_eq!(order, expected);
+            assert_eq!(cars[round as usize * 4..(round as usize + 1) * 4], order);
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            reveal("owner secret"),
+        );
+        assert!(matches!(res, Err(ContractError::SeedClosed)));
+    }
+
+    #[test]
+    fn test_shell_many_players() {
+        for num_players in [5, 8] {
+            // Shells bought in a row are far ahead of their schedule
+            let mut deps = register_deps_with_config(Config {
+                num_players,
+                init_balance: 1_000_000,
+                ..Config::default()
+            });
+            for i in 1..=num_players {
+                set_car(&mut deps, &format!("car{}", i), i * 10, 5, 0);
+            }
+
+            // car2 shells the car right in front of it
+            let msg = ExecuteMsg::BuyShell { amount: 1 };
+            buy(&mut deps, "car2", msg.clone()).unwrap();
+            assert_eq!(load_car(&deps, "car3").speed, 1);
+            for i in (1..=num_players).filter(|i| *i != 3) {
+                assert_eq!(load_car(&deps, &format!("car{}", i)).speed, 5);
+            }
+
+            // A shield protects the car in front
+            set_car(&mut deps, "car4", 40, 5, 1);
+            buy(&mut deps, "car3", msg.clone()).unwrap();
+            assert_eq!(load_car(&deps, "car4").speed, 5);
+
+            // A banana in the way is hit instead of the car
+            let mut game_state = GAME_STATE.load(&deps.storage).unwrap();
+            game_state.bananas = vec![num_players * 10 - 5];
+            GAME_STATE.save(&mut deps.storage, &game_state).unwrap();
+            let last_but_one = format!("car{}", num_players - 1);
+            let last = format!("car{}", num_players);
+            buy(&mut deps, &last_but_one, msg.clone()).unwrap();
+            assert_eq!(load_car(&deps, &last).speed, 5);
+            assert!(GAME_STATE.load(&deps.storage).unwrap().bananas.is_empty());
+
+            // Nobody is in front of the first car
+            let res = buy(&mut deps, &last, msg).unwrap();
+            assert!(res.attributes.contains(&attr("action", "buy_shell")));
+        }
+    }
+
+    #[test]
+    fn test_super_shell_many_players() {
+        for num_players in [5, 8] {
+            let mut deps = register_deps_with_config(Config {
+                num_players,
+                ..Config::default()
+            });
+            for i in 1..=num_players {
+                // Shields do not protect from super shells
+                set_car(&mut deps, &format!("car{}", i), i * 10, 5, i % 2);
+            }
+
+            let msg = ExecuteMsg::BuySuperShell { amount: 1 };
+            buy(&mut deps, "car3", msg).unwrap();
+
+            for i in 1..=num_players {
+                let expected_speed = if i > 3 { 1 } else { 5 };
+                assert_eq!(load_car(&deps, &format!("car{}", i)).speed, expected_speed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_standings_many_players() {
+        let num_players = 8;
+        let mut deps = register_deps_with_config(Config {
+            num_players,
+            ..Config::default()
+        });
+        for i in 1..=num_players {
+            set_car(&mut deps, &format!("car{}", i), (i * 7) % 8 * 10, 0, 0);
+        }
+
+        let StandingsResponse { cars } =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetStandings).unwrap())
+                .unwrap();
+        assert_eq!(cars.len(), num_players as usize);
+        assert!(cars.windows(2).all(|pair| pair[0].y >= pair[1].y));
+        assert_eq!(cars[0].addr, Addr::unchecked("car1"));
+    }
+
+    #[test]
+    fn test_crank() {
+        let mut deps = register_deps();
+        let msg = ExecuteMsg::Crank { turns: 1 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg);
+        assert!(matches!(res, Err(ContractError::CrankDisabled)));
+
+        let mut deps = register_deps_with_config(Config {
+            crank_enabled: true,
+            crank_max_turns: 3,
+            crank_min_blocks: 5,
+            crank_reward: Some(coin(10, "uaura")),
+            ..Config::default()
+        });
+
+        let msg = ExecuteMsg::FundCrankPool {};
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("funder", &coins(25, "other")),
+            msg.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::InvalidFunds { .. })));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("funder", &coins(25, "uaura")),
+            msg,
+        );
+        assert!(res.is_ok());
+
+        let msg = ExecuteMsg::Crank { turns: 4 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg);
+        assert!(matches!(
+            res,
+            Err(ContractError::CrankTurnsExceeded { max: 3 })
+        ));
+
+        let msg = ExecuteMsg::Crank { turns: 2 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        let msgs = reply_turns(&mut deps, res);
+        assert!(msgs.contains(&SubMsg::new(BankMsg::Send {
+            to_address: "anyone".to_string(),
+            amount: coins(20, "uaura"),
+        })));
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        assert_eq!(game_state.turns, 2);
+
+        // Too early for the next crank
+        let msg = ExecuteMsg::Crank { turns: 1 };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::CrankTooEarly { .. })));
+
+        // The pool only has 5 left
+        let mut env = mock_env();
+        env.block.height += 5;
+        let res = execute(deps.as_mut(), env, mock_info("other", &[]), msg).unwrap();
+        let msgs = reply_turns(&mut deps, res);
+        assert!(msgs.contains(&SubMsg::new(BankMsg::Send {
+            to_address: "other".to_string(),
+            amount: coins(5, "uaura"),
+        })));
+
+        let CrankPoolResponse { pool, .. } =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetCrankPool).unwrap())
+                .unwrap();
+        assert!(pool.is_zero());
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut deps = instantiate_deps();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("not_owner", &[]),
+            ExecuteMsg::Pause {},
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Pause {},
+        );
+        assert!(res.is_ok());
+
+        let PausedResponse { paused } =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::IsPaused).unwrap()).unwrap();
+        assert!(paused);
+
+        let msg = ExecuteMsg::Register {
+            car_addrs: vec![
+                Addr::unchecked("car1"),
+                Addr::unchecked("car2"),
+                Addr::unchecked("car3"),
+            ],
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Paused)));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+
+        let msgs = vec![
+            ExecuteMsg::Play { turns_to_play: 1 },
+            ExecuteMsg::BuyAccelerate { amount: 1 },
+            ExecuteMsg::BuyShell { amount: 1 },
+            ExecuteMsg::BuySuperShell { amount: 1 },
+            ExecuteMsg::BuyBanana {},
+            ExecuteMsg::BuyShield { amount: 1 },
+        ];
+        for msg in msgs {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+            assert!(matches!(res, Err(ContractError::Paused)));
+        }
+
+        // Queries keep working
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetGameState);
+        assert!(res.is_ok());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetAllCarData);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_abort() {
+        let mut deps = register_deps_with_config(Config {
+            crank_enabled: true,
+            crank_reward: Some(coin(10, "uaura")),
+            ..Config::default()
+        });
+
+        for (funder, amount) in [("funder1", 30), ("funder2", 10)] {
+            let msg = ExecuteMsg::FundCrankPool {};
+            let info = mock_info(funder, &coins(amount, "uaura"));
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // Two turns are rewarded, 20 is left in the pool
+        let msg = ExecuteMsg::Crank { turns: 2 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        reply_turns(&mut deps, res);
+
+        let msg = ExecuteMsg::Abort {
+            reason: "rules bug".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        // Abort still works while paused
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "funder1".to_string(),
+                    amount: coins(15, "uaura"),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "funder2".to_string(),
+                    amount: coins(5, "uaura"),
+                }),
+            ]
+        );
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::GameNotActive)));
+
+        let cancelled = State::Cancelled {
+            reason: "rules bug".to_string(),
+        };
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        assert_eq!(game_state.state, cancelled);
+
+        let CrankPoolResponse { pool, .. } =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetCrankPool).unwrap())
+                .unwrap();
+        assert!(pool.is_zero());
+
+        // The cancelled race is still queryable after a reset
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Reset {},
+        )
+        .unwrap();
+        let msg = QueryMsg::GetRaceHistory { game_id: 0 };
+        let RaceRecord { game, cars } =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(game.state, cancelled);
+        assert_eq!(game.turns, 2);
+        assert_eq!(cars.len(), 3);
+
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        assert_eq!(game_state.game_id, 1);
+        assert_eq!(game_state.state, State::Waiting);
+    }
+
+    #[test]
+    fn test_crank_pool_per_race() {
+        let mut deps = register_deps_with_config(Config {
+            crank_enabled: true,
+            crank_reward: Some(coin(10, "uaura")),
+            ..Config::default()
+        });
+        let fund = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, funder| {
+            let info = mock_info(funder, &coins(30, "uaura"));
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                ExecuteMsg::FundCrankPool {},
+            )
+        };
+        fund(&mut deps, "funder0").unwrap();
+
+        // What is left of the pool of a race goes back to its funders with the reset
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::Reset {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "funder0".to_string(),
+                amount: coins(30, "uaura"),
+            })]
+        );
+
+        let msg = ExecuteMsg::Register {
+            car_addrs: (1..=3)
+                .map(|i| Addr::unchecked(format!("car{}", i)))
+                .collect(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        fund(&mut deps, "funder1").unwrap();
+
+        // A deposit left in the pool of an earlier race is not paid by the abort
+        CRANK_POOL
+            .save(&mut deps.storage, 0, &Uint128::new(50))
+            .unwrap();
+        CRANK_DEPOSITS
+            .save(
+                &mut deps.storage,
+                (0, Addr::unchecked("funder0")),
+                &Uint128::new(50),
+            )
+            .unwrap();
+        let msg = ExecuteMsg::Abort {
+            reason: "rules bug".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "funder1".to_string(),
+                amount: coins(30, "uaura"),
+            })]
+        );
+        assert_eq!(CRANK_POOL.load(&deps.storage, 0).unwrap(), Uint128::new(50));
+        assert!(CRANK_POOL.may_load(&deps.storage, 1).unwrap().is_none());
+
+        // Nothing more is funded for the cancelled race
+        let res = fund(&mut deps, "funder1");
+        assert!(matches!(res, Err(ContractError::RaceOver)));
+    }
+
+    #[test]
+    fn test_query_owner() {
+        let deps = instantiate_deps();
+
+        let msg = QueryMsg::GetOwner;
+
+        let res = query(deps.as_ref(), mock_env(), msg);
+
+        assert!(res.is_ok());
+
+        let OwnerResponse { owner, .. } = from_binary(&res.unwrap()).unwrap();
+
+        println!("res: {:?}", owner.unwrap());
+
+        let deps = register_deps();
+
+        let msg = QueryMsg::GetOwner;
+
+        let res = query(deps.as_ref(), mock_env(), msg);
+
+        assert!(res.is_ok());
+
+        let OwnerResponse { owner, .. } = from_binary(&res.unwrap()).unwrap();
+
+        println!("res: {:?}", owner.unwrap());
+    }
+
+    #[test]
+    fn test_query_all_car_data() {
+        let deps = instantiate_deps();
+        let msg = QueryMsg::GetAllCarData;
+        let res = query(deps.as_ref(), mock_env(), msg);
+        assert!(res.is_ok());
+        let AllCarDataReponse { all_cars } = from_binary(&res.unwrap()).unwrap();
+        println!("all_cars: {:?}", all_cars);
+        assert!(all_cars.is_empty());
+
+        let deps = register_deps();
+        let msg = QueryMsg::GetAllCarData {};
+        let res = query(deps.as_ref(), mock_env(), msg);
+        assert!(res.is_ok());
+        let AllCarDataReponse { all_cars } = from_binary(&res.unwrap()).unwrap();
+        println!("all_cars: {:?}", all_cars);
+        assert!(all_cars.len() == 3);
+    }
+
+    #[test]
+    fn test_query_game_state() {
+        let deps = instantiate_deps();
+        let msg = QueryMsg::GetGameState;
+        let res = query(deps.as_ref(), mock_env(), msg);
+        assert!(res.is_ok());
+        let GameStateResponse {
+            turns,
+            config,
+            preset,
+            state,
+        } = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(preset, None);
+        println!("turns: {:?}", turns);
+        assert!(turns == 0);
+        println!("config: {:?}", config);
+        println!("state: {:?}", state);
+        assert!(state == State::Waiting);
+
+        let deps = register_deps();
+        let msg = QueryMsg::GetGameState;
+        let res = query(deps.as_ref(), mock_env(), msg);
+        assert!(res.is_ok());
+        let GameStateResponse {
+            turns,
+            config,
+            preset,
+            state,
+        } = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(preset, None);
+        println!("turns: {:?}", turns);
+        assert!(turns == 0);
+        println!("config: {:?}", config);
+        println!("state: {:?}", state);
+        assert!(state == State::Active);
+    }
+
+    #[test]
+    fn test_get_cars_sorted_by_y() {
+        let deps = register_deps();
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+
+        let cars = get_cars_sorted_by_y(deps.as_ref(), &game_state);
+        assert!(cars.len() == 3);
+        println!("cars: {:?}", cars);
+    }
+
+    #[test]
+    fn test_get_all_car_data_and_find_car() {
+        let deps = register_deps();
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+
+        let all_car_data = get_all_car_data(deps.as_ref(), &game_state);
+
+        println!("all_car_data: {:?}", all_car_data);
+    }
+
+    #[test]
+    fn test_play() {
+        let mut deps = register_deps_with_config(Config {
+            target_distance: 100,
+            ..Config::default()
+        });
+        set_car(&mut deps, "car1", 0, 10, 0);
+        set_car(&mut deps, "car2", 0, 20, 2);
+        set_car(&mut deps, "car3", 0, 30, 0);
+
+        // A banana stops car3 and is consumed
+        let mut game_state = GAME_STATE.load(&deps.storage).unwrap();
+        game_state.bananas = vec![25];
+        GAME_STATE.save(&mut deps.storage, &game_state).unwrap();
+
+        let msg = ExecuteMsg::Play { turns_to_play: 2 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        reply_turns(&mut deps, res);
+
+        assert_eq!(load_car(&deps, "car1").y, 20);
+        assert_eq!(load_car(&deps, "car2").y, 40);
+        assert_eq!(load_car(&deps, "car2").shield, 0);
+        assert_eq!(load_car(&deps, "car3").y, 25);
+        assert_eq!(load_car(&deps, "car3").speed, 0);
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        assert!(game_state.bananas.is_empty());
+        assert_eq!(game_state.turns, 2);
+
+        // car2 crosses the line first
+        let msg = ExecuteMsg::Play { turns_to_play: 10 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        reply_turns(&mut deps, res);
+
+        let game_state = GAME_STATE.load(&deps.storage).unwrap();
+        assert_eq!(game_state.state, State::Done);
+        assert_eq!(game_state.turns, 5);
+        assert_eq!(load_car(&deps, "car2").y, 100);
+
+        let msg = ExecuteMsg::Play { turns_to_play: 1 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(res, Err(ContractError::GameNotActive)));
+    }
+
+    #[test]
+    fn test_play_car_error() {
+        let mut deps = register_deps();
+        set_car(&mut deps, "car1", 0, 10, 0);
+
+        let msg = ExecuteMsg::Play { turns_to_play: 1 };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        // The car has failed its turn, the race goes on
+        let reply_msg = Reply {
+            id: TAKE_TURN_REPLY_ID,
+            result: SubMsgResult::Err("out of gas".to_string()),
+        };
+        let res = reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+        assert!(res.attributes.contains(&attr("car_error", "out of gas")));
+        assert_eq!(load_car(&deps, "car1").y, 10);
+        assert_eq!(GAME_STATE.load(&deps.storage).unwrap().turns, 1);
+    }
+
+    #[test]
+    fn test_turn_lock() {
+        let mut deps = register_deps();
+
+        let msg = ExecuteMsg::Play { turns_to_play: 1 };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        // car1 is taking its turn, the race can not be managed until the reply
+        for msg in [
+            ExecuteMsg::Play { turns_to_play: 1 },
+            ExecuteMsg::Crank { turns: 1 },
+            ExecuteMsg::Reset {},
+            ExecuteMsg::Register {
+                car_addrs: vec![Addr::unchecked("car1"), Addr::unchecked("car2")],
+            },
+            ExecuteMsg::Abort {
+                reason: "reentrant".to_string(),
+            },
+        ] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+            assert!(matches!(res, Err(ContractError::TurnInProgress { ref car }) if car == "car1"));
+        }
+
+        // Only car1 buys during its turn
+        let msg = ExecuteMsg::BuyAccelerate { amount: 1 };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("car2", &[]),
+            msg.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::NotCarTurn { ref car }) if car == "car1"));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("car1", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+
+        let reply_msg = Reply {
+            id: TAKE_TURN_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        // Released with the reply, nobody buys between the turns
+        for car in ["car1", "car2"] {
+            let res = execute(deps.as_mut(), mock_env(), mock_info(car, &[]), msg.clone());
+            assert!(matches!(res, Err(ContractError::NoTurnInProgress)));
+        }
+        let msg = ExecuteMsg::Play { turns_to_play: 1 };
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
 }
@@ -1,5 +1,6 @@
 use cosmwasm_std::StdError;
 use thiserror::Error;
+use titans_engine::EngineError;
 
 #[derive(Error, Debug)]
 pub enum ContractError {
@@ -17,4 +18,115 @@ pub enum ContractError {
 
     #[error("Not enough players")]
     NotEnoughPlayers,
+
+    #[error("A race needs between 2 and 8 players, got {num_players}")]
+    InvalidNumPlayers { num_players: u64 },
+
+    #[error("Car {car} is registered twice")]
+    DuplicateCar { car: String },
+
+    #[error("Unknown action {action}")]
+    UnknownAction { action: String },
+
+    #[error("Action {action} is configured twice")]
+    DuplicateAction { action: String },
+
+    #[error("Action {action} needs a per turn decrease below 1 and a sell per turn above 0")]
+    InvalidPricing { action: String },
+
+    #[error("Can not buy {amount} {action} at once")]
+    InvalidActionAmount { action: String, amount: u64 },
+
+    #[error("Insufficient balance: {balance} < {cost}")]
+    InsufficientBalance { balance: u64, cost: u64 },
+
+    #[error("{action} overflows the car {car}")]
+    Overflow { car: String, action: String },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Turn order shuffle is disabled")]
+    ShuffleDisabled,
+
+    #[error("Turn order seed is closed")]
+    SeedClosed,
+
+    #[error("Turn order seed is not revealed yet")]
+    SeedNotRevealed,
+
+    #[error("Already committed")]
+    AlreadyCommitted,
+
+    #[error("No commitment")]
+    NoCommitment,
+
+    #[error("Commitment must be a hex encoded sha256")]
+    InvalidCommitment,
+
+    #[error("Secret does not match the commitment")]
+    InvalidSecret,
+
+    #[error("No pending owner")]
+    NoPendingOwner,
+
+    #[error("Preset {name} not found")]
+    PresetNotFound { name: String },
+
+    #[error("Preset name can not be empty")]
+    InvalidPresetName,
+
+    #[error("Game in progress")]
+    GameInProgress,
+
+    #[error("Contract is paused")]
+    Paused,
+
+    #[error("Car {car} is taking its turn")]
+    TurnInProgress { car: String },
+
+    #[error("Only {car} can buy during its turn")]
+    NotCarTurn { car: String },
+
+    #[error("Actions are only bought by a car during its turn")]
+    NoTurnInProgress,
+
+    #[error("Game is not active")]
+    GameNotActive,
+
+    #[error("Permissionless crank is disabled")]
+    CrankDisabled,
+
+    #[error("Crank can play at most {max} turns")]
+    CrankTurnsExceeded { max: u64 },
+
+    #[error("Crank too early, next crank at height {next_height}")]
+    CrankTooEarly { next_height: u64 },
+
+    #[error("Race is over, reset it first")]
+    RaceOver,
+
+    #[error("Crank pool is not empty")]
+    CrankPoolNotEmpty,
+
+    #[error("Only {denom} is accepted")]
+    InvalidFunds { denom: String },
+}
+
+impl From<EngineError> for ContractError {
+    fn from(err: EngineError) -> Self {
+        match err {
+            EngineError::ZeroAmount => ContractError::ZeroAmount,
+            // Only the cars of the race can buy actions
+            EngineError::UnknownCar { .. } => ContractError::Unauthorized {},
+            EngineError::UnknownAction { action } => ContractError::UnknownAction { action },
+            EngineError::InvalidActionAmount { action, amount } => {
+                ContractError::InvalidActionAmount { action, amount }
+            }
+            EngineError::InsufficientBalance { balance, cost } => {
+                ContractError::InsufficientBalance { balance, cost }
+            }
+            EngineError::Overflow { car, action } => ContractError::Overflow { car, action },
+        }
+    }
 }
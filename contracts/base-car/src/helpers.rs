@@ -1,98 +1,158 @@
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg};
+use sha2::{Digest, Sha256};
 
-use crate::{msg::ExecuteMsg, state::GameState};
+use cosmwasm_std::{to_binary, Addr, Order, StdResult, Storage, SubMsg, WasmMsg};
+use titans_engine::{Car, Race};
 
-/// CwTemplateContract is a wrapper around Addr that provides a lot of helpers
-/// for working with this.
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
-pub struct CwTemplateContract(pub Addr);
+use crate::{
+    contract::TAKE_TURN_REPLY_ID,
+    msg::CarExecuteMsg,
+    state::{
+        CarData, Config, GameState, SeedReveal, ACTION_SOLD, ALL_CAR_DATA, CAR_ACTION_SOLD,
+        MAX_PLAYERS, MIN_PLAYERS, OPERATORS, OWNER, PAUSED, TURN_LOCK,
+    },
+    ContractError,
+};
 
-impl CwTemplateContract {
-    pub fn addr(&self) -> Addr {
-        self.0.clone()
+pub use titans_engine::pricing::{compute_action_price, get_action_cost};
+pub use titans_engine::shuffle_seats;
+
+pub fn assert_owner(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    let owner = OWNER.load(storage)?;
+
+    if *sender != owner {
+        return Err(ContractError::Unauthorized {});
     }
+    Ok(())
+}
 
-    pub fn call<T: Into<ExecuteMsg>>(&self, msg: T) -> StdResult<CosmosMsg> {
-        let msg = to_binary(&msg.into())?;
-        Ok(WasmMsg::Execute {
-            contract_addr: self.addr().into(),
-            msg,
-            funds: vec![],
-        }
-        .into())
+// Operators are allowed to manage races, the owner is always allowed
+pub fn assert_owner_or_operator(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    if OPERATORS.has(storage, sender.clone()) {
+        return Ok(());
     }
+    assert_owner(storage, sender)
 }
 
-pub fn get_accel_cost(state: &GameState, amount: u64, sold: u64) -> u64 {
-    let mut sum = 0 as u64;
-    for i in 0..amount {
-        sum += compute_action_price(
-            state.config.accel_target_price,
-            state.config.accel_per_turn_decrease,
-            state.turns,
-            sold + i,
-            state.config.accel_sell_per_turn,
-        );
+pub fn assert_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    if PAUSED.may_load(storage)?.unwrap_or_default() {
+        return Err(ContractError::Paused);
     }
-    sum
+    Ok(())
 }
 
-pub fn get_shell_cost(state: &GameState, amount: u64, sold: u64) -> u64 {
-    let mut sum = 0 as u64;
-
-    for i in 0..amount {
-        sum += compute_action_price(
-            state.config.shell_target_price,
-            state.config.shell_per_turn_decrease,
-            state.turns,
-            sold + i,
-            state.config.shell_sell_per_turn,
-        );
+// A car taking its turn can not play, register, reset or abort a race from there
+pub fn assert_not_in_turn(storage: &dyn Storage) -> Result<(), ContractError> {
+    if let Some(car) = TURN_LOCK.may_load(storage)? {
+        return Err(ContractError::TurnInProgress {
+            car: car.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// A config a race can be played with, checked when it is set and when it is saved as a preset
+pub fn assert_valid_config(config: &Config) -> Result<(), ContractError> {
+    if config.num_players < MIN_PLAYERS || config.num_players > MAX_PLAYERS {
+        return Err(ContractError::InvalidNumPlayers {
+            num_players: config.num_players,
+        });
+    }
+
+    for (i, action) in config.actions.iter().enumerate() {
+        if config.actions[..i].iter().any(|a| a.name == action.name) {
+            return Err(ContractError::DuplicateAction {
+                action: action.name.clone(),
+            });
+        }
+        if !action.pricing.is_valid() {
+            return Err(ContractError::InvalidPricing {
+                action: action.name.clone(),
+            });
+        }
     }
-    sum
+    Ok(())
 }
 
-pub fn get_super_shell_cost(state: &GameState, amount: u64, sold: u64) -> u64 {
-    let mut sum = 0 as u64;
-
-    for i in 0..amount {
-        sum += compute_action_price(
-            state.config.ss_target_price,
-            state.config.ss_per_turn_decrease,
-            state.turns,
-            sold + i,
-            state.config.ss_sell_per_turn,
-        );
+// TakeTurn sub message for the car playing the current turn, replied even when the car fails
+// The car playing gets the control of the contract until the reply, it is locked in its turn
+pub fn take_turn_msg(storage: &mut dyn Storage, state: &GameState) -> StdResult<SubMsg> {
+    TURN_LOCK.save(storage, &state.current_turn_car())?;
+    Ok(SubMsg::reply_always(
+        WasmMsg::Execute {
+            contract_addr: state.current_turn_car().into(),
+            msg: to_binary(&CarExecuteMsg::TakeTurn {})?,
+            funds: vec![],
+        },
+        TAKE_TURN_REPLY_ID,
+    ))
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+// The turn order seed is the sha256 of all the revealed secrets, each one prefixed by its length
+pub fn get_turn_order_seed(seed_reveals: &[SeedReveal]) -> String {
+    let mut hasher = Sha256::new();
+    for reveal in seed_reveals {
+        hasher.update((reveal.secret.len() as u64).to_be_bytes());
+        hasher.update(reveal.secret.as_bytes());
     }
-    sum
+    hex::encode(hasher.finalize())
 }
 
-pub fn get_banana_cost(state: &GameState, sold: u64) -> u64 {
-    compute_action_price(
-        state.config.banana_target_price,
-        state.config.banana_per_turn_decrease,
-        state.turns,
+// The race of the game state, played by the engine
+pub fn load_race(storage: &dyn Storage, state: &GameState) -> StdResult<Race> {
+    let cars = state
+        .all_cars
+        .iter()
+        .map(|car_addr| Ok(ALL_CAR_DATA.load(storage, car_addr.clone())?.to_car()))
+        .collect::<StdResult<Vec<Car>>>()?;
+
+    let sold = ACTION_SOLD
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<BTreeMap<String, u64>>>()?;
+    let mut car_sold: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+    for item in CAR_ACTION_SOLD.range(storage, None, None, Order::Ascending) {
+        let ((car_addr, action), amount) = item?;
+        car_sold
+            .entry(car_addr.to_string())
+            .or_default()
+            .insert(action, amount);
+    }
+
+    Ok(Race {
+        rules: state.config.rules(),
+        cars,
+        bananas: get_bananas_sorted_by_y(state),
+        turns: state.turns,
         sold,
-        state.config.banana_sell_per_turn,
-    )
+        car_sold,
+        winner: None,
+    })
 }
 
-pub fn get_shield_cost(state: &GameState, amount: u64, sold: u64) -> u64 {
-    let mut sum = 0;
-
-    for i in 0..amount {
-        sum += compute_action_price(
-            state.config.shield_target_price,
-            state.config.shield_per_turn_decrease,
-            state.turns,
-            sold + i,
-            state.config.shield_sell_per_turn,
-        );
+// Save the race played by the engine, the winner is left to the caller
+pub fn save_race(storage: &mut dyn Storage, state: &mut GameState, race: &Race) -> StdResult<()> {
+    for car in race.cars.iter() {
+        let car_data = CarData::from_car(car);
+        ALL_CAR_DATA.save(storage, car_data.addr.clone(), &car_data)?;
+    }
+
+    for (action, amount) in race.sold.iter() {
+        ACTION_SOLD.save(storage, action, amount)?;
+    }
+    for (car, sold) in race.car_sold.iter() {
+        for (action, amount) in sold.iter() {
+            CAR_ACTION_SOLD.save(storage, (Addr::unchecked(car), action), amount)?;
+        }
     }
-    sum
+
+    state.bananas = race.bananas.clone();
+    state.turns = race.turns;
+    Ok(())
 }
 
 pub fn get_bananas_sorted_by_y(state: &GameState) -> Vec<u64> {
@@ -101,10 +161,7 @@ pub fn get_bananas_sorted_by_y(state: &GameState) -> Vec<u64> {
     for i in 0..sorted.len() {
         for j in (i + 1)..sorted.len() {
             if sorted[j] < sorted[i] {
-                // Swap using xor operation
-                sorted[i] = sorted[j] ^ sorted[i];
-                sorted[i] = sorted[i] ^ sorted[j];
-                sorted[j] = sorted[j] ^ sorted[i];
+                sorted.swap(i, j);
             }
         }
     }
@@ -112,24 +169,11 @@ pub fn get_bananas_sorted_by_y(state: &GameState) -> Vec<u64> {
     sorted
 }
 
-pub fn compute_action_price(
-    target_price: u64,
-    per_turn_price_decrease: u64,
-    turn_since_start: u64,
-    sold: u64,
-    sell_per_turn_wad: u64,
-) -> u64 {
-    1
-}
-
 #[cfg(test)]
 pub mod tests {
-    use crate::{
-        helpers::{get_shell_cost, get_super_shell_cost, get_shield_cost, get_banana_cost},
-        state::GameState,
-    };
+    use crate::state::{GameState, ACCELERATE, BANANA, SHELL, SHIELD, SUPER_SHELL};
 
-    use super::{get_accel_cost, get_bananas_sorted_by_y};
+    use super::{get_action_cost, get_bananas_sorted_by_y, shuffle_seats};
 
     #[test]
     fn test_get_bananas_sorted_by_y() {
@@ -140,29 +184,43 @@ pub mod tests {
         println!("{:?}", res);
     }
 
+    #[test]
+    fn test_shuffle_seats() {
+        let seats = shuffle_seats("seed", 0, 8);
+        let mut sorted = seats.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..8).collect::<Vec<usize>>());
+
+        // Same seed and round, same order
+        assert_eq!(seats, shuffle_seats("seed", 0, 8));
+
+        // Another round or another seed gives a different order
+        assert_ne!(seats, shuffle_seats("seed", 1, 8));
+        assert_ne!(seats, shuffle_seats("other seed", 0, 8));
+    }
+
     #[test]
     fn test_get_cost() {
         let test_state = GameState::for_test();
 
-        let accel_cost = get_accel_cost(&test_state, 2, 3);
-        assert!(accel_cost != 0);
-        println!("{:?}", accel_cost);
-
-        let shell_cost = get_shell_cost(&test_state, 2, 3);
-        assert!(shell_cost != 0);
-        println!("{:?}", shell_cost);
-
-        let ss_cost = get_super_shell_cost(&test_state, 2, 3);
-        assert!(ss_cost != 0);
-        println!("{:?}", ss_cost);
-
-        let shield_cost = get_shield_cost(&test_state, 2, 3);
-        assert!(shield_cost != 0);
-        println!("{:?}", shield_cost);
-
-        let banana_cost = get_banana_cost(&test_state, 2);
-        assert!(banana_cost != 0);
-        println!("{:?}", banana_cost);
+        // Two more of each action at the turn 3 once 3 are sold: far ahead of the schedule
+        // of the attacks, a bit behind for accelerate (6 + 8)
+        let expected = [
+            (ACCELERATE, 14),
+            (SHELL, 1_521_895),
+            (SUPER_SHELL, 4_372_229),
+            (BANANA, 1_521_895),
+            (SHIELD, 1_141_421),
+        ];
+        assert_eq!(test_state.config.actions.len(), expected.len());
+        for (action, (name, cost)) in test_state.config.actions.iter().zip(expected) {
+            assert_eq!(action.name, name);
+            assert_eq!(
+                get_action_cost(&action.pricing, test_state.turns, 2, 3, u64::MAX),
+                cost,
+                "{}",
+                name
+            );
+        }
     }
 }
-
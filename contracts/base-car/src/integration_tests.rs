@@ -1,37 +1,1188 @@
 #![cfg(test)]
 
+use std::cell::RefCell;
 
-use cosmwasm_std::Empty;
-use cw_multi_test::{App, Contract, ContractWrapper, Executor};
-
-use crate::contract::{execute, instantiate, query};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, DepsMut, Empty, Env, MessageInfo, Response};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use titans_car_sdk::CarExecuteMsg;
+use titans_engine::pricing::WAD;
 
+use crate::contract::{execute, instantiate, query, reply};
+use crate::msg::{
+    ExecuteMsg, GameStateResponse, InstantiateMsg, OwnerResponse, QueryMsg, StandingsResponse,
+};
+use crate::state::{CarData, Config, PricingSpec, State, ACCELERATE, SHELL};
+use crate::wasm_car::{WasmCar, GAS_LIMIT};
+use crate::ContractError;
 
 fn mock_app() -> App {
     App::default()
 }
 
+thread_local! {
+    // Errors of the base contract, cw-multi-test only reports the failed car message when
+    // a message sent by a car fails
+    static BASE_CAR_ERRORS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
 pub fn contract_base_car() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(execute, instantiate, query);
+    fn execute_recording_errors(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        execute(deps, env, info, msg).inspect_err(|err| {
+            BASE_CAR_ERRORS.with(|errors| errors.borrow_mut().push(err.to_string()));
+        })
+    }
+
+    let contract =
+        ContractWrapper::new(execute_recording_errors, instantiate, query).with_reply(reply);
     Box::new(contract)
 }
 
+// The errors returned by the base contract since the last call, in this thread
+pub fn take_base_car_errors() -> Vec<String> {
+    BASE_CAR_ERRORS.with(|errors| errors.take())
+}
+
 pub fn contract_car_1() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(car_1::contract::execute, car_1::contract::instantiate, car_1::contract::query);
+    let contract = ContractWrapper::new(
+        car_1::contract::execute,
+        car_1::contract::instantiate,
+        car_1::contract::query,
+    );
     Box::new(contract)
 }
 
-
 pub fn contract_car_2() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(car_2::contract::execute, car_2::contract::instantiate, car_2::contract::query);
+    let contract = ContractWrapper::new(
+        car_2::contract::execute,
+        car_2::contract::instantiate,
+        car_2::contract::query,
+    );
     Box::new(contract)
 }
 
 pub fn contract_car_3() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(car_3::contract::execute, car_3::contract::instantiate, car_3::contract::query);
+    let contract = ContractWrapper::new(
+        car_3::contract::execute,
+        car_3::contract::instantiate,
+        car_3::contract::query,
+    );
+    Box::new(contract)
+}
+
+pub fn contract_car_params() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        car_params::contract::execute,
+        car_params::contract::instantiate,
+        car_params::contract::query,
+    );
+    Box::new(contract)
+}
+
+pub fn contract_car_manual() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        car_manual::contract::execute,
+        car_manual::contract::instantiate,
+        car_manual::contract::query,
+    );
+    Box::new(contract)
+}
+
+pub fn contract_car_proxy() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        car_proxy::contract::execute,
+        car_proxy::contract::instantiate,
+        car_proxy::contract::query,
+    )
+    .with_reply(car_proxy::contract::reply);
+    Box::new(contract)
+}
+
+pub fn contract_car_script() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        car_script::contract::execute,
+        car_script::contract::instantiate,
+        car_script::contract::query,
+    );
+    Box::new(contract)
+}
+
+// A car playing a fixed list of orders, one list per turn of the car
+pub fn contract_scripted_car() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        scripted_car::execute,
+        scripted_car::instantiate,
+        scripted_car::query,
+    );
+    Box::new(contract)
+}
+
+mod scripted_car {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+        StdResult,
+    };
+    use cw_storage_plus::Item;
+    use titans_car_sdk::{BaseCarClient, CarExecuteMsg};
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub base_car_addr: Addr,
+        pub script: Vec<Vec<(String, u64)>>,
+    }
+
+    const BASE_CAR_ADDR: Item<Addr> = Item::new("base_car_addr");
+    const SCRIPT: Item<Vec<Vec<(String, u64)>>> = Item::new("script");
+    const TURNS_PLAYED: Item<usize> = Item::new("turns_played");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        BASE_CAR_ADDR.save(deps.storage, &msg.base_car_addr)?;
+        SCRIPT.save(deps.storage, &msg.script)?;
+        TURNS_PLAYED.save(deps.storage, &0)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: CarExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            CarExecuteMsg::TakeTurn {} => {
+                let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+                let turn = TURNS_PLAYED.load(deps.storage)?;
+                TURNS_PLAYED.save(deps.storage, &(turn + 1))?;
+
+                let orders = SCRIPT
+                    .load(deps.storage)?
+                    .get(turn)
+                    .cloned()
+                    .unwrap_or_default();
+                let msgs = orders
+                    .iter()
+                    .map(|(action, amount)| base_car.buy(action, *amount))
+                    .collect::<StdResult<Vec<CosmosMsg>>>()?;
+                Ok(Response::new().add_messages(msgs))
+            }
+            CarExecuteMsg::Ping {} => Ok(Response::new()),
+        }
+    }
+
+    pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Err(StdError::generic_err("no query"))
+    }
+}
+
+// A car calling back the base contract during its turn
+pub fn contract_reentrant_car() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        reentrant_car::execute,
+        reentrant_car::instantiate,
+        reentrant_car::query,
+    );
+    Box::new(contract)
+}
+
+mod reentrant_car {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+        StdResult, WasmMsg,
+    };
+    use cw_storage_plus::Item;
+    use titans_car_sdk::{BaseCarClient, CarExecuteMsg, ACCELERATE};
+
+    use crate::msg::ExecuteMsg;
+
+    // Each attack goes through when the base contract does not lock the turn
+    #[cw_serde]
+    pub enum Attack {
+        // Play our own turn again, the nested turn does not attack
+        Play,
+        Crank,
+        Reset,
+        // Reset the race and register a new one with an accomplice
+        Register { accomplice: Addr },
+        Abort,
+        // Ping an accomplice car, which buys during our turn
+        BuyThrough { accomplice: Addr },
+    }
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub base_car_addr: Addr,
+        pub attack: Option<Attack>,
+    }
+
+    const BASE_CAR_ADDR: Item<Addr> = Item::new("base_car_addr");
+    const ATTACK: Item<Option<Attack>> = Item::new("attack");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        BASE_CAR_ADDR.save(deps.storage, &msg.base_car_addr)?;
+        ATTACK.save(deps.storage, &msg.attack)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        _info: MessageInfo,
+        msg: CarExecuteMsg,
+    ) -> StdResult<Response> {
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        match msg {
+            CarExecuteMsg::TakeTurn {} => {
+                // Attack once, a turn played from the attack does not attack again
+                let Some(attack) = ATTACK.load(deps.storage)? else {
+                    return Ok(Response::new());
+                };
+                ATTACK.save(deps.storage, &None)?;
+
+                let base_car_msg = |msg: &ExecuteMsg| -> StdResult<WasmMsg> {
+                    Ok(WasmMsg::Execute {
+                        contract_addr: base_car.addr().to_string(),
+                        msg: to_binary(msg)?,
+                        funds: vec![],
+                    })
+                };
+                let msgs = match attack {
+                    Attack::Play => vec![base_car_msg(&ExecuteMsg::Play { turns_to_play: 1 })?],
+                    Attack::Crank => vec![base_car_msg(&ExecuteMsg::Crank { turns: 1 })?],
+                    Attack::Reset => vec![base_car_msg(&ExecuteMsg::Reset {})?],
+                    Attack::Register { accomplice } => vec![
+                        base_car_msg(&ExecuteMsg::Reset {})?,
+                        base_car_msg(&ExecuteMsg::Register {
+                            car_addrs: vec![env.contract.address, accomplice],
+                        })?,
+                    ],
+                    Attack::Abort => vec![base_car_msg(&ExecuteMsg::Abort {
+                        reason: "reentrant".to_string(),
+                    })?],
+                    Attack::BuyThrough { accomplice } => vec![WasmMsg::Execute {
+                        contract_addr: accomplice.to_string(),
+                        msg: to_binary(&CarExecuteMsg::Ping {})?,
+                        funds: vec![],
+                    }],
+                };
+
+                Ok(Response::new().add_messages(msgs))
+            }
+            CarExecuteMsg::Ping {} => Ok(Response::new().add_message(base_car.buy(ACCELERATE, 1)?)),
+        }
+    }
+
+    pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Err(StdError::generic_err("no query"))
+    }
+}
+
+// A strategy of the proxy car, the same orders on every turn
+pub fn contract_fixed_strategy() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        fixed_strategy::execute,
+        fixed_strategy::instantiate,
+        fixed_strategy::query,
+    );
     Box::new(contract)
 }
 
+mod fixed_strategy {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+    };
+    use cw_storage_plus::Item;
+    use titans_car_sdk::{StrategyExecuteMsg, StrategyResponse};
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub orders: Vec<(String, u64)>,
+    }
+
+    const ORDERS: Item<Vec<(String, u64)>> = Item::new("orders");
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> StdResult<Response> {
+        ORDERS.save(deps.storage, &msg.orders)?;
+        Ok(Response::new())
+    }
+
+    pub fn execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: StrategyExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            StrategyExecuteMsg::TakeTurn { .. } => {
+                let orders = ORDERS.load(deps.storage)?;
+                Ok(Response::new().set_data(to_binary(&StrategyResponse { orders })?))
+            }
+        }
+    }
+
+    pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Err(StdError::generic_err("no query"))
+    }
+}
+
+// Instantiate message of the example cars
+#[cw_serde]
+struct CarInstantiateMsg {
+    base_car_addr: Addr,
+}
+
+// Turns played by each Play message, a race is played in batches
+const TURNS_PER_PLAY: u64 = 10;
+
+// A race between car contracts on a `cw_multi_test::App`
+pub struct RaceFixture {
+    pub app: App,
+    pub owner: Addr,
+    pub base_car: Addr,
+    pub cars: Vec<Addr>,
+}
+
+#[derive(Debug)]
+pub struct RaceResult {
+    pub state: State,
+    pub winner: Option<Addr>,
+    pub turns: u64,
+    // Cars from the first to the last one
+    pub standings: Vec<CarData>,
+    // Errors of the turns lost by the cars
+    pub car_errors: Vec<String>,
+}
+
+impl RaceResult {
+    pub fn car(&self, car: &Addr) -> &CarData {
+        self.standings.iter().find(|c| c.addr == car).unwrap()
+    }
+}
+
+impl RaceFixture {
+    // Instantiate the base contract and register the example cars, in seat order
+    pub fn new(config: Config, cars: Vec<Box<dyn Contract<Empty>>>) -> Self {
+        let mut fixture = Self::with_base(config);
+        let car_addrs = cars
+            .into_iter()
+            .enumerate()
+            .map(|(i, car)| {
+                let msg = CarInstantiateMsg {
+                    base_car_addr: fixture.base_car.clone(),
+                };
+                fixture.instantiate_car(car, &msg, i)
+            })
+            .collect();
+        fixture.register(car_addrs);
+        fixture
+    }
+
+    // Instantiate the base contract and register scripted cars, one script per car
+    pub fn scripted(config: Config, scripts: Vec<Vec<Vec<(String, u64)>>>) -> Self {
+        let mut fixture = Self::with_base(config);
+        let car_addrs = scripts
+            .into_iter()
+            .enumerate()
+            .map(|(i, script)| {
+                let msg = scripted_car::InstantiateMsg {
+                    base_car_addr: fixture.base_car.clone(),
+                    script,
+                };
+                fixture.instantiate_car(contract_scripted_car(), &msg, i)
+            })
+            .collect();
+        fixture.register(car_addrs);
+        fixture
+    }
+
+    fn with_base(config: Config) -> Self {
+        let mut app = mock_app();
+        let owner = Addr::unchecked("owner");
+        let code_id = app.store_code(contract_base_car());
+        let base_car = app
+            .instantiate_contract(
+                code_id,
+                owner.clone(),
+                &InstantiateMsg {
+                    owner: owner.to_string(),
+                },
+                &[],
+                "base-car",
+                None,
+            )
+            .unwrap();
+        app.execute_contract(
+            owner.clone(),
+            base_car.clone(),
+            &ExecuteMsg::UpdateConfig {
+                config: Box::new(config),
+            },
+            &[],
+        )
+        .unwrap();
+
+        Self {
+            app,
+            owner,
+            base_car,
+            cars: vec![],
+        }
+    }
+
+    fn instantiate_car<T: serde::Serialize>(
+        &mut self,
+        car: Box<dyn Contract<Empty>>,
+        msg: &T,
+        seat: usize,
+    ) -> Addr {
+        let code_id = self.app.store_code(car);
+        self.app
+            .instantiate_contract(
+                code_id,
+                self.owner.clone(),
+                msg,
+                &[],
+                format!("car-{}", seat),
+                None,
+            )
+            .unwrap()
+    }
+
+    fn register(&mut self, car_addrs: Vec<Addr>) {
+        self.app
+            .execute_contract(
+                self.owner.clone(),
+                self.base_car.clone(),
+                &ExecuteMsg::Register {
+                    car_addrs: car_addrs.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+        self.cars = car_addrs;
+    }
+
+    pub fn play(&mut self, turns_to_play: u64) -> AppResponse {
+        self.app
+            .execute_contract(
+                self.owner.clone(),
+                self.base_car.clone(),
+                &ExecuteMsg::Play { turns_to_play },
+                &[],
+            )
+            .unwrap()
+    }
+
+    // Play until a car wins, or `max_turns` turns are played
+    pub fn run(&mut self, max_turns: u64) -> RaceResult {
+        let mut winner = None;
+        let mut car_errors = vec![];
+
+        while self.game_state().state == State::Active && self.game_state().turns < max_turns {
+            let turns = TURNS_PER_PLAY.min(max_turns - self.game_state().turns);
+            let res = self.play(turns);
+            for attr in res.events.iter().flat_map(|event| event.attributes.iter()) {
+                match attr.key.as_str() {
+                    "winner" => winner = Some(Addr::unchecked(&attr.value)),
+                    "car_error" => car_errors.push(attr.value.clone()),
+                    _ => {}
+                }
+            }
+        }
+
+        let game_state = self.game_state();
+        RaceResult {
+            state: game_state.state,
+            winner,
+            turns: game_state.turns,
+            standings: self.standings(),
+            car_errors,
+        }
+    }
+
+    pub fn game_state(&self) -> GameStateResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(&self.base_car, &QueryMsg::GetGameState)
+            .unwrap()
+    }
+
+    pub fn standings(&self) -> Vec<CarData> {
+        let res: StandingsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.base_car, &QueryMsg::GetStandings)
+            .unwrap();
+        res.cars
+    }
+}
+
+// The default config with every unit at a flat price, the balances are easy to follow
+fn flat_price_config(num_players: u64, price: u64) -> Config {
+    let mut config = Config {
+        num_players,
+        ..Config::default()
+    };
+    for action in config.actions.iter_mut() {
+        action.pricing = PricingSpec {
+            target_price: price,
+            per_turn_decrease: 0,
+            sell_per_turn: WAD,
+        };
+    }
+    config
+}
+
+fn orders(orders: &[(&str, u64)]) -> Vec<(String, u64)> {
+    orders
+        .iter()
+        .map(|(action, amount)| (action.to_string(), *amount))
+        .collect()
+}
+
 #[test]
-fn test_basic() {    
-}
\ No newline at end of file
+fn test_basic() {
+    let mut app = mock_app();
+    let owner = Addr::unchecked("owner");
+
+    let base_car_code_id = app.store_code(contract_base_car());
+    for car in [contract_car_1(), contract_car_2(), contract_car_3()] {
+        app.store_code(car);
+    }
+
+    let base_car_addr = app
+        .instantiate_contract(
+            base_car_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: owner.to_string(),
+            },
+            &[],
+            "base-car",
+            None,
+        )
+        .unwrap();
+
+    let res: OwnerResponse = app
+        .wrap()
+        .query_wasm_smart(base_car_addr, &QueryMsg::GetOwner)
+        .unwrap();
+    assert_eq!(res.owner, Some(owner.to_string()));
+}
+
+#[test]
+fn test_example_cars_race() {
+    let mut fixture = RaceFixture::new(
+        Config::default(),
+        vec![contract_car_1(), contract_car_2(), contract_car_3()],
+    );
+    let result = fixture.run(1000);
+
+    assert_eq!(result.state, State::Done);
+    assert!(result.car_errors.is_empty());
+    let winner = result.winner.unwrap();
+    assert_eq!(result.standings[0].addr, winner);
+    assert!(result.standings[0].y >= Config::default().target_distance);
+
+    // Every car has bought something
+    for car in result.standings.iter() {
+        assert!(car.balance < Config::default().init_balance);
+    }
+}
+
+#[test]
+fn test_scripted_race() {
+    let config = flat_price_config(2, 1);
+    let mut fixture = RaceFixture::scripted(
+        config,
+        vec![
+            vec![orders(&[(ACCELERATE, 10)])],
+            vec![orders(&[(ACCELERATE, 5)])],
+        ],
+    );
+    let result = fixture.run(1000);
+
+    // The first car moves by 10 each turn and crosses the line on the 100th turn, the race
+    // ends before the second car moves on that turn
+    assert_eq!(result.state, State::Done);
+    assert_eq!(result.winner, Some(fixture.cars[0].clone()));
+    assert_eq!(result.turns, 100);
+    assert_eq!(result.car(&fixture.cars[0]).y, 1000);
+    assert_eq!(result.car(&fixture.cars[0]).balance, 17490);
+    assert_eq!(result.car(&fixture.cars[1]).y, 5 * 98);
+    assert_eq!(result.car(&fixture.cars[1]).balance, 17495);
+}
+
+#[test]
+fn test_scripted_race_shell() {
+    let config = flat_price_config(2, 1);
+    let mut fixture = RaceFixture::scripted(
+        config,
+        vec![
+            vec![orders(&[(ACCELERATE, 10)]), orders(&[(SHELL, 1)])],
+            vec![orders(&[(ACCELERATE, 30)])],
+        ],
+    );
+    let result = fixture.run(1000);
+
+    // The second car takes the lead, it is shelled back to speed 1 on the third turn
+    assert_eq!(result.winner, Some(fixture.cars[0].clone()));
+    assert_eq!(result.turns, 100);
+    assert_eq!(result.car(&fixture.cars[0]).balance, 17500 - 10 - 1);
+    assert_eq!(result.car(&fixture.cars[1]).speed, 1);
+    assert_eq!(result.car(&fixture.cars[1]).y, 31 + 96);
+    assert_eq!(result.car(&fixture.cars[1]).balance, 17500 - 30);
+}
+
+#[test]
+fn test_scripted_race_car_error() {
+    let config = Config {
+        init_balance: 15,
+        target_distance: 100,
+        ..flat_price_config(2, 1)
+    };
+    let mut fixture = RaceFixture::scripted(
+        config,
+        vec![
+            vec![orders(&[(ACCELERATE, 10)])],
+            vec![orders(&[(ACCELERATE, 20)]), orders(&[(ACCELERATE, 5)])],
+        ],
+    );
+    let result = fixture.run(1000);
+
+    // The second car can not afford its order, the whole turn of the car is reverted with
+    // its script position, so it fails again on each of its turns
+    assert_eq!(result.car_errors.len(), 5);
+    assert!(result.car_errors[0].contains("take_turn"));
+    assert_eq!(result.car(&fixture.cars[1]).balance, 15);
+    assert_eq!(result.car(&fixture.cars[1]).y, 0);
+    assert_eq!(result.winner, Some(fixture.cars[0].clone()));
+    assert_eq!(result.turns, 10);
+}
+
+#[test]
+fn test_run_max_turns() {
+    let config = Config {
+        num_players: 2,
+        ..Config::default()
+    };
+    let mut fixture = RaceFixture::scripted(config, vec![vec![], vec![]]);
+    let result = fixture.run(25);
+
+    // Nobody moves, the race is still running
+    assert_eq!(result.state, State::Active);
+    assert_eq!(result.winner, None);
+    assert_eq!(result.turns, 25);
+}
+
+impl RaceFixture {
+    // A car buying outside of the turns played by the base contract
+    fn buy_directly(&mut self, car: usize) -> ContractError {
+        self.app
+            .execute_contract(
+                self.cars[car].clone(),
+                self.base_car.clone(),
+                &ExecuteMsg::BuyAccelerate { amount: 5 },
+                &[],
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    }
+}
+
+#[test]
+fn test_buy_outside_turn() {
+    let config = Config {
+        num_players: 2,
+        ..Config::default()
+    };
+    let scripts = vec![
+        vec![orders(&[(ACCELERATE, 10)])],
+        vec![orders(&[(ACCELERATE, 5)])],
+    ];
+
+    // Between the turns of a race
+    let mut fixture = RaceFixture::scripted(config.clone(), scripts.clone());
+    fixture.play(4);
+    let err = fixture.buy_directly(1);
+    assert!(matches!(err, ContractError::NoTurnInProgress));
+
+    // After an abort
+    fixture
+        .app
+        .execute_contract(
+            fixture.owner.clone(),
+            fixture.base_car.clone(),
+            &ExecuteMsg::Abort {
+                reason: "test".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    let standings = fixture.standings();
+    let err = fixture.buy_directly(1);
+    assert!(matches!(err, ContractError::GameNotActive));
+    assert!(matches!(
+        fixture.game_state().state,
+        State::Cancelled { .. }
+    ));
+    assert_eq!(fixture.standings(), standings);
+
+    // After the finish
+    let mut fixture = RaceFixture::scripted(config, scripts);
+    let result = fixture.run(1000);
+    assert_eq!(result.state, State::Done);
+    let err = fixture.buy_directly(1);
+    assert!(matches!(err, ContractError::GameNotActive));
+    assert_eq!(fixture.standings(), result.standings);
+}
+
+#[test]
+fn test_params_car_race() {
+    let config = Config {
+        num_players: 2,
+        ..Config::default()
+    };
+    let mut fixture = RaceFixture::with_base(config);
+    let params_car = fixture.instantiate_car(
+        contract_car_params(),
+        &car_params::msg::InstantiateMsg {
+            base_car_addr: fixture.base_car.clone(),
+            params: titans_car_sdk::StrategyParams::default(),
+        },
+        0,
+    );
+    let car_2 = fixture.instantiate_car(
+        contract_car_2(),
+        &CarInstantiateMsg {
+            base_car_addr: fixture.base_car.clone(),
+        },
+        1,
+    );
+    fixture.register(vec![params_car.clone(), car_2]);
+
+    // The params are fixed for the race
+    fixture.play(5);
+    let update = car_params::msg::ExecuteMsg::UpdateParams {
+        params: titans_car_sdk::StrategyParams {
+            target_speed: 40,
+            ..Default::default()
+        },
+    };
+    let res = fixture
+        .app
+        .execute_contract(fixture.owner.clone(), params_car.clone(), &update, &[]);
+    assert!(res.is_err());
+
+    let result = fixture.run(1000);
+    assert_eq!(result.state, State::Done);
+    assert!(result.car_errors.is_empty());
+    assert!(result.car(&params_car).balance < Config::default().init_balance);
+
+    fixture
+        .app
+        .execute_contract(fixture.owner.clone(), params_car, &update, &[])
+        .unwrap();
+}
+
+#[test]
+fn test_script_car_race() {
+    use car_script::script::{Cmp, Condition, Order, Rule, Subject};
+
+    let condition = |subject, op, value| Condition { subject, op, value };
+    let order = |action: &str, amount| Order {
+        action: action.to_string(),
+        amount,
+    };
+    let rules = vec![
+        Rule {
+            when: vec![condition(Subject::Speed, Cmp::Lt, 10)],
+            then: vec![order(ACCELERATE, 2)],
+        },
+        Rule {
+            when: vec![
+                condition(Subject::Rank, Cmp::Ge, 1),
+                condition(Subject::DistanceToLeader, Cmp::Gt, 20),
+            ],
+            then: vec![order(SHELL, 1)],
+        },
+    ];
+
+    let config = Config {
+        num_players: 2,
+        ..Config::default()
+    };
+    let mut fixture = RaceFixture::with_base(config);
+    let script_car = fixture.instantiate_car(
+        contract_car_script(),
+        &car_script::msg::InstantiateMsg {
+            base_car_addr: fixture.base_car.clone(),
+            rules: rules.clone(),
+        },
+        0,
+    );
+    let car_2 = fixture.instantiate_car(
+        contract_car_2(),
+        &CarInstantiateMsg {
+            base_car_addr: fixture.base_car.clone(),
+        },
+        1,
+    );
+    fixture.register(vec![script_car.clone(), car_2]);
+
+    // The first turn of the car accelerates by 2
+    fixture.play(1);
+    let car = fixture
+        .standings()
+        .into_iter()
+        .find(|car| car.addr == script_car)
+        .unwrap();
+    assert_eq!(car.speed, 2);
+
+    // The rules are fixed for the race
+    let set = car_script::msg::ExecuteMsg::SetRules { rules };
+    let res = fixture
+        .app
+        .execute_contract(fixture.owner.clone(), script_car.clone(), &set, &[]);
+    assert!(res.is_err());
+
+    let result = fixture.run(1000);
+    assert_eq!(result.state, State::Done);
+    assert!(result.car_errors.is_empty());
+
+    fixture
+        .app
+        .execute_contract(fixture.owner.clone(), script_car, &set, &[])
+        .unwrap();
+}
+
+#[test]
+fn test_manual_car_race() {
+    let config = Config {
+        num_players: 2,
+        ..Config::default()
+    };
+    let mut fixture = RaceFixture::with_base(config);
+    let manual_car = fixture.instantiate_car(
+        contract_car_manual(),
+        &car_manual::msg::InstantiateMsg {
+            base_car_addr: fixture.base_car.clone(),
+            fallback: orders(&[(ACCELERATE, 1)]),
+        },
+        0,
+    );
+    // Does not buy anything
+    let idle_car = fixture.instantiate_car(
+        contract_scripted_car(),
+        &scripted_car::InstantiateMsg {
+            base_car_addr: fixture.base_car.clone(),
+            script: vec![],
+        },
+        1,
+    );
+    fixture.register(vec![manual_car.clone(), idle_car]);
+
+    // The car plays the even turns: 5 on turn 0, nothing on turn 2, the fallback on turn 4
+    // and an unaffordable plan falls back on turn 6
+    for (turn, actions) in [
+        (0, orders(&[(ACCELERATE, 5)])),
+        (2, vec![]),
+        (6, orders(&[(ACCELERATE, 1_000_000)])),
+    ] {
+        fixture
+            .app
+            .execute_contract(
+                fixture.owner.clone(),
+                manual_car.clone(),
+                &car_manual::msg::ExecuteMsg::QueueActions { turn, actions },
+                &[],
+            )
+            .unwrap();
+    }
+
+    let speed = |fixture: &RaceFixture| {
+        fixture
+            .standings()
+            .into_iter()
+            .find(|car| car.addr == manual_car)
+            .unwrap()
+            .speed
+    };
+    fixture.play(1);
+    assert_eq!(speed(&fixture), 5);
+    fixture.play(2);
+    assert_eq!(speed(&fixture), 5);
+    fixture.play(2);
+    assert_eq!(speed(&fixture), 6);
+    fixture.play(2);
+    assert_eq!(speed(&fixture), 7);
+
+    // Played turns are out of the queue
+    let plan: car_manual::msg::PlanResponse = fixture
+        .app
+        .wrap()
+        .query_wasm_smart(&manual_car, &car_manual::msg::QueryMsg::GetPlan {})
+        .unwrap();
+    assert!(plan.queue.is_empty());
+}
+
+#[test]
+fn test_proxy_car_race() {
+    let config = Config {
+        num_players: 2,
+        ..Config::default()
+    };
+    let mut fixture = RaceFixture::with_base(config);
+    let strategy_code = fixture.app.store_code(contract_fixed_strategy());
+    let mut strategy = |orders| {
+        fixture
+            .app
+            .instantiate_contract(
+                strategy_code,
+                Addr::unchecked("strategist"),
+                &fixed_strategy::InstantiateMsg { orders },
+                &[],
+                "strategy",
+                None,
+            )
+            .unwrap()
+    };
+    let strategy_v1 = strategy(orders(&[(ACCELERATE, 2)]));
+    let strategy_v2 = strategy(orders(&[(ACCELERATE, 3)]));
+
+    let proxy_car = fixture.instantiate_car(
+        contract_car_proxy(),
+        &car_proxy::msg::InstantiateMsg {
+            base_car_addr: fixture.base_car.clone(),
+            strategy: strategy_v1,
+        },
+        0,
+    );
+    let idle_car = fixture.instantiate_car(
+        contract_scripted_car(),
+        &scripted_car::InstantiateMsg {
+            base_car_addr: fixture.base_car.clone(),
+            script: vec![],
+        },
+        1,
+    );
+    fixture.register(vec![proxy_car.clone(), idle_car]);
+
+    // The orders of the strategy are bought by the proxy
+    fixture.play(4);
+    let car = fixture
+        .standings()
+        .into_iter()
+        .find(|car| car.addr == proxy_car)
+        .unwrap();
+    assert_eq!(car.speed, 4);
+
+    // The strategy is fixed for the race
+    let swap = car_proxy::msg::ExecuteMsg::SetStrategy {
+        strategy: strategy_v2.clone(),
+    };
+    let res = fixture
+        .app
+        .execute_contract(fixture.owner.clone(), proxy_car.clone(), &swap, &[]);
+    assert!(res.is_err());
+
+    // Only the base contract makes the proxy play
+    let res = fixture.app.execute_contract(
+        fixture.owner.clone(),
+        proxy_car.clone(),
+        &car_proxy::msg::ExecuteMsg::TakeTurn {},
+        &[],
+    );
+    assert!(res.is_err());
+
+    let result = fixture.run(1000);
+    assert_eq!(result.state, State::Done);
+    assert_eq!(result.winner, Some(proxy_car.clone()));
+    assert!(result.car_errors.is_empty());
+
+    fixture
+        .app
+        .execute_contract(fixture.owner.clone(), proxy_car.clone(), &swap, &[])
+        .unwrap();
+    let res: car_proxy::msg::ProxyResponse = fixture
+        .app
+        .wrap()
+        .query_wasm_smart(&proxy_car, &car_proxy::msg::QueryMsg::GetProxy {})
+        .unwrap();
+    assert_eq!(res.strategy, strategy_v2);
+}
+
+#[test]
+fn test_reentrant_car() {
+    use reentrant_car::Attack;
+
+    // Each attack would go through without the turn lock
+    let config = Config {
+        num_players: 2,
+        crank_enabled: true,
+        ..Config::default()
+    };
+    for attack in [
+        Attack::Play,
+        Attack::Crank,
+        Attack::Reset,
+        Attack::Register {
+            accomplice: Addr::unchecked(""),
+        },
+        Attack::Abort,
+        Attack::BuyThrough {
+            accomplice: Addr::unchecked(""),
+        },
+    ] {
+        let mut fixture = RaceFixture::with_base(config.clone());
+        let accomplice = fixture.instantiate_car(
+            contract_reentrant_car(),
+            &reentrant_car::InstantiateMsg {
+                base_car_addr: fixture.base_car.clone(),
+                attack: None,
+            },
+            1,
+        );
+        let attack = match attack {
+            Attack::Register { .. } => Attack::Register {
+                accomplice: accomplice.clone(),
+            },
+            Attack::BuyThrough { .. } => Attack::BuyThrough {
+                accomplice: accomplice.clone(),
+            },
+            attack => attack,
+        };
+        let attacker = fixture.instantiate_car(
+            contract_reentrant_car(),
+            &reentrant_car::InstantiateMsg {
+                base_car_addr: fixture.base_car.clone(),
+                attack: Some(attack.clone()),
+            },
+            0,
+        );
+        fixture.register(vec![attacker.clone(), accomplice]);
+
+        // As an operator the attacker is allowed to manage races, but not from its turn
+        fixture
+            .app
+            .execute_contract(
+                fixture.owner.clone(),
+                fixture.base_car.clone(),
+                &ExecuteMsg::AddOperator {
+                    operator: attacker.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+
+        // The nested message fails the turn of the attacker, on the lock of the turn
+        take_base_car_errors();
+        let res = fixture.play(1);
+        assert!(
+            res.events
+                .iter()
+                .flat_map(|event| event.attributes.iter())
+                .any(|attr| attr.key == "car_error"),
+            "{attack:?} was not rejected"
+        );
+        let expected = match attack {
+            Attack::BuyThrough { .. } => ContractError::NotCarTurn {
+                car: attacker.to_string(),
+            },
+            _ => ContractError::TurnInProgress {
+                car: attacker.to_string(),
+            },
+        };
+        assert_eq!(
+            take_base_car_errors(),
+            vec![expected.to_string()],
+            "{attack:?}"
+        );
+
+        // No turn was played from the turn, the race is neither reset nor aborted and the
+        // accomplice did not buy
+        let game_state = fixture.game_state();
+        assert_eq!(game_state.state, State::Active, "{attack:?}");
+        assert_eq!(game_state.turns, 1, "{attack:?}");
+        assert!(fixture.standings().iter().all(|car| car.speed == 0));
+
+        // The attacker only loses its turn, the lock is released for the next one
+        fixture.play(1);
+        assert_eq!(fixture.game_state().turns, 2);
+    }
+}
+
+#[test]
+fn test_wasm_car_race() {
+    let wasm_car = WasmCar::new(include_bytes!("../fixtures/cars/accelerator.wat")).unwrap();
+    let gas_log = wasm_car.gas_log();
+
+    let config = Config {
+        num_players: 2,
+        ..Config::default()
+    };
+    let mut fixture = RaceFixture::with_base(config);
+    let car_msg = CarInstantiateMsg {
+        base_car_addr: fixture.base_car.clone(),
+    };
+    let accelerator = fixture.instantiate_car(Box::new(wasm_car), &car_msg, 0);
+    let car_2 = fixture.instantiate_car(contract_car_2(), &car_msg, 1);
+    fixture.register(vec![accelerator.clone(), car_2]);
+
+    // Only the base contract can make the car play
+    let err = fixture
+        .app
+        .execute_contract(
+            fixture.owner.clone(),
+            accelerator.clone(),
+            &CarExecuteMsg::TakeTurn {},
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("Unauthorized"));
+    gas_log.borrow_mut().clear();
+
+    // The car buys 1 accelerate on each of its turns
+    fixture.play(10);
+    let car = fixture
+        .standings()
+        .into_iter()
+        .find(|car| car.addr == accelerator)
+        .unwrap();
+    assert_eq!(car.speed, 5);
+    assert!(car.balance < Config::default().init_balance);
+
+    // Each TakeTurn is charged
+    assert_eq!(gas_log.borrow().len(), 5);
+    assert!(gas_log
+        .borrow()
+        .iter()
+        .all(|log| log.entry_point == "execute" && log.gas > 0 && log.gas < GAS_LIMIT));
+
+    let result = fixture.run(1000);
+    assert_eq!(result.state, State::Done);
+    assert!(result.car_errors.is_empty());
+}
@@ -3,7 +3,10 @@ mod error;
 pub mod helpers;
 pub mod msg;
 pub mod state;
+#[cfg(any(test, feature = "runner"))]
+pub mod wasm_car;
 
+mod conformance_tests;
 mod integration_tests;
 
 pub use crate::error::ContractError;
@@ -1,209 +1,76 @@
-use std::{fmt::Display, vec};
-
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{Item, Map};
 
-pub const OWNER: Item<String> = Item::new("owner");
-pub const GAME_STATE: Item<GameState> = Item::new("game_state");
-
-// ACTION_SOLD is map of number action has sold
-// example: <"shell", 10> meaning action shell has sold with 10 amount
-pub const ACTION_SOLD: Map<&str, u64> = Map::new("action_sold");
-
-pub const ALL_CAR_DATA: Map<Addr, CarData> = Map::new("all_car_data");
+pub use titans_car_sdk::types::{CarData, Config, GameState, RaceRecord, SeedReveal, State};
+pub use titans_engine::{
+    default_actions, ActionConfig, ActionEffect, ActionSupply, PricingSpec, ACCELERATE, BANANA,
+    SHELL, SHIELD, SUPER_SHELL,
+};
 
-#[cw_serde]
-pub struct Config {
-    // Number players required in each round
-    pub num_players: u64,
+// Number of cars allowed in a race
+pub const MIN_PLAYERS: u64 = 2;
+pub const MAX_PLAYERS: u64 = 8;
 
-    pub post_sell_speed: u64,
+pub const OWNER: Item<Addr> = Item::new("owner");
 
-    // Initial balance for each car
-    pub init_balance: u64,
+// PENDING_OWNER is the address proposed by the current owner, it becomes
+// the owner once it accepts the ownership
+pub const PENDING_OWNER: Item<Addr> = Item::new("pending_owner");
 
-    // Target distance
-    pub target_distance: u64,
+// OPERATORS can manage races (register, reset, play) but can not change
+// the config or the ownership
+pub const OPERATORS: Map<Addr, bool> = Map::new("operators");
 
-    pub banana_speed_modifier: u64,
+pub const GAME_STATE: Item<GameState> = Item::new("game_state");
 
-    // Shell config
-    pub shell_target_price: u64,
-    pub shell_per_turn_decrease: u64,
-    pub shell_sell_per_turn: u64,
+// PLAY_CONTEXT is only set while turns are being played
+pub const PLAY_CONTEXT: Item<PlayContext> = Item::new("play_context");
 
-    // Accelrate config
-    pub accel_target_price: u64,
-    pub accel_per_turn_decrease: u64,
-    pub accel_sell_per_turn: u64,
+// TURN_LOCK is the car whose TakeTurn is executing, set when the sub message is sent and
+// removed in its reply
+pub const TURN_LOCK: Item<Addr> = Item::new("turn_lock");
 
-    // Super shell config
-    pub ss_target_price: u64,
-    pub ss_per_turn_decrease: u64,
-    pub ss_sell_per_turn: u64,
+// PAUSED blocks playing, registering and buying actions until the owner unpauses
+pub const PAUSED: Item<bool> = Item::new("paused");
 
-    // Banana config
-    pub banana_target_price: u64,
-    pub banana_per_turn_decrease: u64,
-    pub banana_sell_per_turn: u64,
+// RACE_HISTORY keeps the finished, cancelled or reset races by their game id
+pub const RACE_HISTORY: Map<u64, RaceRecord> = Map::new("race_history");
 
-    // Shield config
-    pub shield_target_price: u64,
-    pub shield_per_turn_decrease: u64,
-    pub shield_sell_per_turn: u64,
-}
+// SEED_COMMITS is map of hex encoded sha256 of the secret each participant commits to
+// the turn order seed of the current race
+pub const SEED_COMMITS: Map<Addr, String> = Map::new("seed_commits");
 
-impl Config {
-    pub fn default() -> Self {
-        Self {
-            num_players: 3,
-            post_sell_speed: 1,
-            init_balance: 17500,
-            target_distance: 1000,
-            banana_speed_modifier: 0,
-            shell_target_price: 0,
-            shell_per_turn_decrease: 0,
-            shell_sell_per_turn: 0,
-            accel_target_price: 0,
-            accel_per_turn_decrease: 0,
-            accel_sell_per_turn: 0,
-            ss_target_price: 0,
-            ss_per_turn_decrease: 0, // zero
-            ss_sell_per_turn: 0,
-            banana_target_price: 0,
-            banana_per_turn_decrease: 0,
-            banana_sell_per_turn: 0,
-            shield_target_price: 0,
-            shield_per_turn_decrease: 0,
-            shield_sell_per_turn: 0,
-        }
-    }
-}
+// SEED_REVEALS is map of the secrets revealed for the current race
+pub const SEED_REVEALS: Map<Addr, String> = Map::new("seed_reveals");
 
-#[cw_serde]
-pub enum State {
-    Waiting,
-    Active,
-    Done,
-}
+// ACTION_SOLD is map of number action has sold, by the name of the action in `Config.actions`
+// example: <"shell", 10> meaning action shell has sold with 10 amount
+pub const ACTION_SOLD: Map<&str, u64> = Map::new("action_sold");
 
-#[cw_serde]
-#[derive(Eq, Hash)]
-pub enum ActionType {
-    Accelerate,
-    Shell,
-    SuperShell,
-    Banana,
-    Shield,
-}
+// CAR_ACTION_SOLD is map of number action has sold to each car
+// example: <("car1", "shell"), 2> meaning car1 has bought 2 shells
+pub const CAR_ACTION_SOLD: Map<(Addr, &str), u64> = Map::new("car_action_sold");
 
-impl Display for ActionType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match *self {
-            ActionType::Accelerate => write!(f, "accelerate"),
-            ActionType::Shell => write!(f, "shell"),
-            ActionType::SuperShell => write!(f, "super_shell"),
-            ActionType::Banana => write!(f, "banana"),
-            ActionType::Shield => write!(f, "shield"),
-        }
-    }
-}
+// PRESETS is map of the named configs saved by the owner, a race can use one by its name
+pub const PRESETS: Map<&str, Config> = Map::new("presets");
 
-#[cw_serde]
-pub struct CarData {
-    pub balance: u64,
-    pub addr: Addr,
-    pub y: u64,
-    pub speed: u64,
-    pub shield: u64,
-}
+pub const ALL_CAR_DATA: Map<Addr, CarData> = Map::new("all_car_data");
 
-impl CarData {
-    #[warn(dead_code)]
-    fn test_car() -> Self {
-        Self {
-            balance: 1000,
-            addr: Addr::unchecked("input"),
-            y: 10,
-            speed: 2,
-            shield: 0,
-        }
-    }
-}
+// CRANK_POOL is map of the amount left to reward the crankers of each race, by game id, in
+// the denom of `Config.crank_reward`
+pub const CRANK_POOL: Map<u64, Uint128> = Map::new("crank_pool");
 
-impl CarData {
-    pub fn at_start(addr: Addr) -> Self {
-        Self {
-            balance: 17500,
-            addr: addr,
-            y: 0,
-            speed: 0,
-            shield: 0,
-        }
-    }
-
-    pub fn empty() -> Self {
-        Self {
-            balance: 0,
-            addr: Addr::unchecked(""),
-            y: 0,
-            speed: 0,
-            shield: 0,
-        }
-    }
-}
+// CRANK_DEPOSITS is map of the amount each funder has put in the crank pool of a race
+// example: <(0, "funder1"), 30> meaning funder1 has put 30 in the pool of the race 0
+pub const CRANK_DEPOSITS: Map<(u64, Addr), Uint128> = Map::new("crank_deposits");
 
 #[cw_serde]
-pub struct GameState {
-    pub all_cars: Vec<Addr>,
-    pub turns: u64,
-
-    // The current state of the game: pre-start, started, done.
-    pub state: State,
-
-    // Game config
-    pub config: Config,
-
-    // The banana in play, tracked by their y position.
-    pub bananas: Vec<u64>,
-}
+pub struct PlayContext {
+    // Turns left to play in the current Play or Crank
+    pub turns_left: u64,
+    pub turns_played: u64,
 
-impl GameState {
-    pub fn default() -> Self {
-        Self {
-            all_cars: Vec::new(),
-            turns: 0,
-            state: State::Waiting,
-            config: Config::default(),
-            bananas: Vec::new(),
-        }
-    }
-
-    pub fn for_test() -> Self {
-        let addr1 = Addr::unchecked("addr1");
-        let addr2 = Addr::unchecked("addr2");
-        let addr3 = Addr::unchecked("addr3");
-        let all_cars = vec![addr1.clone(), addr2.clone(), addr3.clone()];
-
-        Self {
-            all_cars,
-            turns: 3,
-            state: State::Active,
-            config: Config::default(),
-            bananas: vec![20, 10, 35, 30],
-        }
-    }
-
-    pub fn register(&mut self, car_addrs: Vec<Addr>) {
-        self.all_cars = car_addrs;
-    }
-
-    pub fn total_cars(&self) -> u64 {
-        self.all_cars.len() as u64
-    }
-
-    pub fn can_play(&self) -> bool {
-        return self.all_cars.len() as u64 == self.config.num_players;
-    }
+    // Rewarded once all the turns are played
+    pub cranker: Option<Addr>,
 }
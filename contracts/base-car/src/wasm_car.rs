@@ -0,0 +1,571 @@
+// Car loaded from a compiled .wasm file, to race on cw-multi-test exactly what players
+// deploy. The module runs on the wasmi interpreter with the imports of the CosmWasm 1.x VM,
+// a fresh instance for each call, as the chain does.
+use std::{cell::RefCell, rc::Rc};
+
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{
+    from_slice, to_vec, Api, Binary, CanonicalAddr, ContractResult, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Querier, Record, RecoverPubkeyError, Reply, Response, StdError, StdResult,
+    Storage, VerificationError,
+};
+use cw_multi_test::Contract;
+use wasmi::{
+    core::Trap, AsContext, AsContextMut, Caller, Config, Engine, Extern, Linker, Memory, Module,
+    Store, TypedFunc,
+};
+
+// Gas of a call, the car runs out of gas past it
+pub const GAS_LIMIT: u64 = 1_000_000_000;
+
+// Exports every CosmWasm 1.x contract has
+const REQUIRED_EXPORTS: [&str; 4] = ["interface_version_8", "allocate", "deallocate", "memory"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasLog {
+    pub entry_point: String,
+    // Fuel burnt by wasmi to run the instructions of the car, calls to the host are free
+    pub gas: u64,
+}
+
+pub struct WasmCar {
+    engine: Engine,
+    module: Module,
+    // Gas of each call, in order, until the caller takes it
+    gas_log: Rc<RefCell<Vec<GasLog>>>,
+}
+
+enum HostStorage<'a> {
+    // Queries can only read the storage
+    Read(&'a dyn Storage),
+    Write(&'a mut dyn Storage),
+}
+
+struct Host<'a> {
+    storage: HostStorage<'a>,
+    api: &'a dyn Api,
+    querier: &'a dyn Querier,
+    // Records of each db_scan, iterator ids start at 1
+    iterators: Vec<std::vec::IntoIter<Record>>,
+}
+
+impl WasmCar {
+    // A compiled .wasm file, or a module in the text format
+    pub fn new(wasm: &[u8]) -> StdResult<Self> {
+        let wasm = wat::parse_bytes(wasm).map_err(|err| StdError::generic_err(err.to_string()))?;
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &wasm[..])
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+        let exports: Vec<&str> = module.exports().map(|export| export.name()).collect();
+        if let Some(missing) = REQUIRED_EXPORTS
+            .iter()
+            .find(|export| !exports.contains(export))
+        {
+            return Err(StdError::generic_err(format!(
+                "not a CosmWasm contract, missing export {}",
+                missing
+            )));
+        }
+
+        Ok(Self {
+            engine,
+            module,
+            gas_log: Rc::default(),
+        })
+    }
+
+    // Shared with the contract once it is stored on the App
+    pub fn gas_log(&self) -> Rc<RefCell<Vec<GasLog>>> {
+        self.gas_log.clone()
+    }
+
+    // Call the entry point with JSON arguments, and return the JSON result it wrote
+    fn call(&self, entry_point: &str, host: Host, args: &[&[u8]]) -> StdResult<Vec<u8>> {
+        let mut store = Store::new(&self.engine, host);
+        store.add_fuel(GAS_LIMIT).map_err(trap_err)?;
+        let res = self.call_in(&mut store, entry_point, args);
+
+        self.gas_log.borrow_mut().push(GasLog {
+            entry_point: entry_point.to_string(),
+            gas: store.fuel_consumed().unwrap_or_default(),
+        });
+        res
+    }
+
+    fn call_in(
+        &self,
+        store: &mut Store<Host>,
+        entry_point: &str,
+        args: &[&[u8]],
+    ) -> StdResult<Vec<u8>> {
+        let instance = linker(&self.engine)
+            .map_err(trap_err)?
+            .instantiate(&mut *store, &self.module)
+            .and_then(|instance| instance.start(&mut *store))
+            .map_err(trap_err)?;
+        let func = instance
+            .get_func(&*store, entry_point)
+            .ok_or_else(|| StdError::generic_err(format!("the car has no {}", entry_point)))?;
+        let exports = Exports {
+            memory: instance.get_memory(&*store, "memory").unwrap(),
+            allocate: instance
+                .get_typed_func(&*store, "allocate")
+                .map_err(trap_err)?,
+        };
+
+        let mut ptrs = vec![];
+        for arg in args {
+            ptrs.push(
+                exports
+                    .allocate_region(&mut *store, arg)
+                    .map_err(trap_err)?,
+            );
+        }
+        let ptr = match ptrs[..] {
+            [env, msg] => func
+                .typed::<(u32, u32), u32>(&*store)
+                .and_then(|func| func.call(&mut *store, (env, msg)).map_err(Into::into)),
+            [env, info, msg] => func
+                .typed::<(u32, u32, u32), u32>(&*store)
+                .and_then(|func| func.call(&mut *store, (env, info, msg)).map_err(Into::into)),
+            _ => unreachable!(),
+        }
+        .map_err(trap_err)?;
+        exports.read_region(&*store, ptr).map_err(trap_err)
+    }
+
+    fn call_response(
+        &self,
+        entry_point: &str,
+        deps: DepsMut,
+        args: &[&[u8]],
+    ) -> AnyResult<Response> {
+        let host = Host::new(HostStorage::Write(deps.storage), deps.api, &*deps.querier);
+        let res: ContractResult<Response> = from_slice(&self.call(entry_point, host, args)?)?;
+        Ok(res.into_result().map_err(StdError::generic_err)?)
+    }
+}
+
+impl Contract<Empty> for WasmCar {
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response> {
+        self.call_response("execute", deps, &[&to_vec(&env)?, &to_vec(&info)?, &msg])
+    }
+
+    fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response> {
+        self.call_response(
+            "instantiate",
+            deps,
+            &[&to_vec(&env)?, &to_vec(&info)?, &msg],
+        )
+    }
+
+    fn query(&self, deps: Deps, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        let host = Host::new(HostStorage::Read(deps.storage), deps.api, &*deps.querier);
+        let res: ContractResult<Binary> =
+            from_slice(&self.call("query", host, &[&to_vec(&env)?, &msg])?)?;
+        Ok(res.into_result().map_err(StdError::generic_err)?)
+    }
+
+    fn sudo(&self, deps: DepsMut, env: Env, msg: Vec<u8>) -> AnyResult<Response> {
+        self.call_response("sudo", deps, &[&to_vec(&env)?, &msg])
+    }
+
+    fn reply(&self, deps: DepsMut, env: Env, msg: Reply) -> AnyResult<Response> {
+        self.call_response("reply", deps, &[&to_vec(&env)?, &to_vec(&msg)?])
+    }
+
+    fn migrate(&self, deps: DepsMut, env: Env, msg: Vec<u8>) -> AnyResult<Response> {
+        self.call_response("migrate", deps, &[&to_vec(&env)?, &msg])
+    }
+}
+
+impl<'a> Host<'a> {
+    fn new(storage: HostStorage<'a>, api: &'a dyn Api, querier: &'a dyn Querier) -> Self {
+        Self {
+            storage,
+            api,
+            querier,
+            iterators: vec![],
+        }
+    }
+
+    fn storage(&self) -> &dyn Storage {
+        match &self.storage {
+            HostStorage::Read(storage) => *storage,
+            HostStorage::Write(storage) => &**storage,
+        }
+    }
+
+    fn storage_mut(&mut self) -> Result<&mut dyn Storage, Trap> {
+        match &mut self.storage {
+            HostStorage::Read(_) => Err(Trap::new("the storage is read only in queries")),
+            HostStorage::Write(storage) => Ok(&mut **storage),
+        }
+    }
+}
+
+// Memory and allocator of the instance, to pass data in regions
+struct Exports {
+    memory: Memory,
+    allocate: TypedFunc<u32, u32>,
+}
+
+impl Exports {
+    fn of(caller: &Caller<Host>) -> Result<Self, Trap> {
+        let memory = caller
+            .get_export("memory")
+            .and_then(Extern::into_memory)
+            .ok_or_else(|| Trap::new("the car has no memory"))?;
+        let allocate = caller
+            .get_export("allocate")
+            .and_then(Extern::into_func)
+            .ok_or_else(|| Trap::new("the car has no allocate"))?
+            .typed(caller)
+            .map_err(|err| Trap::new(err.to_string()))?;
+        Ok(Self { memory, allocate })
+    }
+
+    // A region is { offset, capacity, length } in little endian u32
+    fn region(&self, ctx: impl AsContext, ptr: u32) -> Result<[u32; 3], Trap> {
+        let mut bytes = [0u8; 12];
+        self.memory
+            .read(ctx, ptr as usize, &mut bytes)
+            .map_err(|err| Trap::new(err.to_string()))?;
+        let field = |i: usize| u32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap());
+        Ok([field(0), field(1), field(2)])
+    }
+
+    fn read_region(&self, ctx: impl AsContext, ptr: u32) -> Result<Vec<u8>, Trap> {
+        let [offset, _, length] = self.region(&ctx, ptr)?;
+        let mut data = vec![0u8; length as usize];
+        self.memory
+            .read(&ctx, offset as usize, &mut data)
+            .map_err(|err| Trap::new(err.to_string()))?;
+        Ok(data)
+    }
+
+    // Into a region allocated by the car
+    fn write_region(&self, mut ctx: impl AsContextMut, ptr: u32, data: &[u8]) -> Result<(), Trap> {
+        let [offset, capacity, _] = self.region(&ctx, ptr)?;
+        if data.len() > capacity as usize {
+            return Err(Trap::new("the region is too small"));
+        }
+        self.memory
+            .write(&mut ctx, offset as usize, data)
+            .and_then(|_| {
+                let length = (data.len() as u32).to_le_bytes();
+                self.memory.write(&mut ctx, ptr as usize + 8, &length)
+            })
+            .map_err(|err| Trap::new(err.to_string()))
+    }
+
+    fn allocate_region(&self, mut ctx: impl AsContextMut, data: &[u8]) -> Result<u32, Trap> {
+        let ptr = self.allocate.call(&mut ctx, data.len() as u32)?;
+        self.write_region(ctx, ptr, data)?;
+        Ok(ptr)
+    }
+}
+
+// Imports of the CosmWasm 1.x VM, debug messages are dropped as on chain
+fn linker<'a>(engine: &Engine) -> Result<Linker<Host<'a>>, wasmi::errors::LinkerError> {
+    let mut linker = Linker::new(engine);
+
+    linker.func_wrap("env", "db_read", |mut caller: Caller<Host>, key: u32| {
+        let exports = Exports::of(&caller)?;
+        let key = exports.read_region(&caller, key)?;
+        match caller.data().storage().get(&key) {
+            Some(value) => exports.allocate_region(&mut caller, &value),
+            None => Ok(0),
+        }
+    })?;
+    linker.func_wrap(
+        "env",
+        "db_write",
+        |mut caller: Caller<Host>, key: u32, value: u32| {
+            let exports = Exports::of(&caller)?;
+            let key = exports.read_region(&caller, key)?;
+            let value = exports.read_region(&caller, value)?;
+            caller.data_mut().storage_mut()?.set(&key, &value);
+            Ok(())
+        },
+    )?;
+    linker.func_wrap("env", "db_remove", |mut caller: Caller<Host>, key: u32| {
+        let key = Exports::of(&caller)?.read_region(&caller, key)?;
+        caller.data_mut().storage_mut()?.remove(&key);
+        Ok(())
+    })?;
+    linker.func_wrap(
+        "env",
+        "db_scan",
+        |mut caller: Caller<Host>, start: u32, end: u32, order: i32| {
+            let exports = Exports::of(&caller)?;
+            let bound = |ptr: u32| match ptr {
+                0 => Ok(None),
+                ptr => exports.read_region(&caller, ptr).map(Some),
+            };
+            let (start, end) = (bound(start)?, bound(end)?);
+            let order = Order::try_from(order).map_err(|err| Trap::new(err.to_string()))?;
+            let records: Vec<Record> = caller
+                .data()
+                .storage()
+                .range(start.as_deref(), end.as_deref(), order)
+                .collect();
+            let iterators = &mut caller.data_mut().iterators;
+            iterators.push(records.into_iter());
+            Ok(iterators.len() as u32)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_next",
+        |mut caller: Caller<Host>, iterator: u32| {
+            let exports = Exports::of(&caller)?;
+            let record = caller
+                .data_mut()
+                .iterators
+                .get_mut((iterator as usize).wrapping_sub(1))
+                .ok_or_else(|| Trap::new(format!("unknown iterator {}", iterator)))?
+                .next();
+            // An empty key ends the iteration
+            let (key, value) = record.unwrap_or_default();
+            exports.allocate_region(&mut caller, &encode_sections(&[&key, &value]))
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "addr_validate",
+        |mut caller: Caller<Host>, source: u32| {
+            let exports = Exports::of(&caller)?;
+            let input = exports.read_region(&caller, source)?;
+            let res = String::from_utf8(input)
+                .map_err(|err| StdError::generic_err(err.to_string()))
+                .and_then(|input| caller.data().api.addr_validate(&input));
+            match res {
+                Ok(_) => Ok(0),
+                Err(err) => exports.allocate_region(&mut caller, err.to_string().as_bytes()),
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "addr_canonicalize",
+        |mut caller: Caller<Host>, source: u32, destination: u32| {
+            let exports = Exports::of(&caller)?;
+            let input = exports.read_region(&caller, source)?;
+            let res = String::from_utf8(input)
+                .map_err(|err| StdError::generic_err(err.to_string()))
+                .and_then(|input| caller.data().api.addr_canonicalize(&input));
+            match res {
+                Ok(canonical) => exports
+                    .write_region(&mut caller, destination, &canonical)
+                    .map(|_| 0),
+                Err(err) => exports.allocate_region(&mut caller, err.to_string().as_bytes()),
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "addr_humanize",
+        |mut caller: Caller<Host>, source: u32, destination: u32| {
+            let exports = Exports::of(&caller)?;
+            let canonical = CanonicalAddr::from(exports.read_region(&caller, source)?);
+            match caller.data().api.addr_humanize(&canonical) {
+                Ok(human) => exports
+                    .write_region(&mut caller, destination, human.as_bytes())
+                    .map(|_| 0),
+                Err(err) => exports.allocate_region(&mut caller, err.to_string().as_bytes()),
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "env",
+        "secp256k1_verify",
+        |caller: Caller<Host>, hash: u32, signature: u32, public_key: u32| {
+            let exports = Exports::of(&caller)?;
+            let [hash, signature, public_key] = [hash, signature, public_key]
+                .map(|ptr| exports.read_region(&caller, ptr))
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?
+                .try_into()
+                .unwrap();
+            let res = caller
+                .data()
+                .api
+                .secp256k1_verify(&hash, &signature, &public_key);
+            Ok(verification_code(res))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "secp256k1_recover_pubkey",
+        |mut caller: Caller<Host>, hash: u32, signature: u32, recovery_param: u32| {
+            let exports = Exports::of(&caller)?;
+            let hash = exports.read_region(&caller, hash)?;
+            let signature = exports.read_region(&caller, signature)?;
+            let res =
+                caller
+                    .data()
+                    .api
+                    .secp256k1_recover_pubkey(&hash, &signature, recovery_param as u8);
+            // The error code in the high half, or the region of the public key
+            let code = match res {
+                Ok(public_key) => {
+                    return Ok(exports.allocate_region(&mut caller, &public_key)? as u64)
+                }
+                Err(RecoverPubkeyError::InvalidHashFormat) => 3,
+                Err(RecoverPubkeyError::InvalidSignatureFormat) => 4,
+                Err(RecoverPubkeyError::InvalidRecoveryParam) => 6,
+                Err(RecoverPubkeyError::UnknownErr { error_code, .. }) => error_code,
+            };
+            Ok((code as u64) << 32)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "ed25519_verify",
+        |caller: Caller<Host>, message: u32, signature: u32, public_key: u32| {
+            let exports = Exports::of(&caller)?;
+            let message = exports.read_region(&caller, message)?;
+            let signature = exports.read_region(&caller, signature)?;
+            let public_key = exports.read_region(&caller, public_key)?;
+            let res = caller
+                .data()
+                .api
+                .ed25519_verify(&message, &signature, &public_key);
+            Ok(verification_code(res))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "ed25519_batch_verify",
+        |caller: Caller<Host>, messages: u32, signatures: u32, public_keys: u32| {
+            let exports = Exports::of(&caller)?;
+            let messages = decode_sections(&exports.read_region(&caller, messages)?)?;
+            let signatures = decode_sections(&exports.read_region(&caller, signatures)?)?;
+            let public_keys = decode_sections(&exports.read_region(&caller, public_keys)?)?;
+            let res = caller.data().api.ed25519_batch_verify(
+                &messages.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+                &signatures.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+                &public_keys.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+            );
+            Ok(verification_code(res))
+        },
+    )?;
+
+    linker.func_wrap("env", "debug", |_: Caller<Host>, _: u32| {})?;
+    linker.func_wrap(
+        "env",
+        "query_chain",
+        |mut caller: Caller<Host>, request: u32| {
+            let exports = Exports::of(&caller)?;
+            let request = exports.read_region(&caller, request)?;
+            let res = caller.data().querier.raw_query(&request);
+            let res = to_vec(&res).map_err(|err| Trap::new(err.to_string()))?;
+            exports.allocate_region(&mut caller, &res)
+        },
+    )?;
+    linker.func_wrap("env", "abort", |caller: Caller<Host>, message: u32| {
+        let message = Exports::of(&caller)?.read_region(&caller, message)?;
+        Err::<(), _>(Trap::new(format!(
+            "Aborted: {}",
+            String::from_utf8_lossy(&message)
+        )))
+    })?;
+
+    Ok(linker)
+}
+
+// 0 when verified, 1 when not, the code of the error otherwise
+fn verification_code(res: Result<bool, VerificationError>) -> u32 {
+    match res {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(VerificationError::InvalidHashFormat) => 3,
+        Err(VerificationError::InvalidSignatureFormat) => 4,
+        Err(VerificationError::InvalidPubkeyFormat) => 5,
+        Err(VerificationError::InvalidRecoveryParam) => 6,
+        Err(VerificationError::BatchErr) => 7,
+        Err(VerificationError::GenericErr) => 10,
+        Err(VerificationError::UnknownErr { error_code, .. }) => error_code,
+    }
+}
+
+// Each section is followed by its length in big endian u32
+fn encode_sections(sections: &[&[u8]]) -> Vec<u8> {
+    let mut data = vec![];
+    for section in sections {
+        data.extend_from_slice(section);
+        data.extend_from_slice(&(section.len() as u32).to_be_bytes());
+    }
+    data
+}
+
+fn decode_sections(mut data: &[u8]) -> Result<Vec<Vec<u8>>, Trap> {
+    let mut sections = vec![];
+    while !data.is_empty() {
+        let split = data
+            .len()
+            .checked_sub(4)
+            .ok_or_else(|| Trap::new("invalid sections"))?;
+        let length = u32::from_be_bytes(data[split..].try_into().unwrap()) as usize;
+        let start = split
+            .checked_sub(length)
+            .ok_or_else(|| Trap::new("invalid sections"))?;
+        sections.push(data[start..split].to_vec());
+        data = &data[..start];
+    }
+    sections.reverse();
+    Ok(sections)
+}
+
+fn trap_err(err: impl ToString) -> StdError {
+    StdError::generic_err(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_sections, encode_sections, WasmCar};
+
+    #[test]
+    fn test_new() {
+        WasmCar::new(include_bytes!("../fixtures/cars/accelerator.wat")).unwrap();
+
+        let err = WasmCar::new(b"not wasm").err().unwrap();
+        assert!(err.to_string().contains("expected"), "{}", err);
+
+        let err = WasmCar::new(br#"(module (memory (export "memory") 1))"#)
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("missing export interface_version_8"));
+    }
+
+    #[test]
+    fn test_sections() {
+        let data = encode_sections(&[b"key", b"", b"value"]);
+        assert_eq!(data, b"key\0\0\0\x03\0\0\0\0value\0\0\0\x05".to_vec());
+        assert_eq!(
+            decode_sections(&data).unwrap(),
+            vec![b"key".to_vec(), vec![], b"value".to_vec()]
+        );
+        assert!(decode_sections(b"\0\0\0\x09").is_err());
+    }
+}
@@ -34,36 +34,161 @@ pub fn execute(
 }
 
 pub mod execute {
-    use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+    use cosmwasm_std::{CosmosMsg, DepsMut, Env, MessageInfo, Response, StdResult};
+    use titans_car_sdk::{BaseCarClient, TurnInfo, ACCELERATE, SHELL, SHIELD};
 
-    use crate::ContractError;
+    use crate::{state::BASE_CAR_ADDR, ContractError};
+
+    // Share of the balance the car spends in one turn, 20 means a twentieth
+    pub const TURN_BUDGET_DIVISOR: u64 = 20;
 
     pub fn execute_take_turn(
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
     ) -> Result<Response, ContractError> {
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        base_car.assert_caller(&info)?;
+
+        // Our position, our rank and the prices of the actions
+        let turn = base_car.turn_info(&deps.querier, &env.contract.address)?;
+
+        // The actions are bought by sending `Buy` messages back to the base contract
+        let orders = decide(&turn);
+        let msgs = orders
+            .iter()
+            .map(|(action, amount)| base_car.buy(action, *amount))
+            .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
         Ok(Response::new()
-            .add_attribute("contract_addr", env.contract.address.clone().to_string())
+            .add_messages(msgs)
+            .add_attribute("contract_addr", env.contract.address.to_string())
+            .add_attribute("y", turn.me.y.to_string())
+            .add_attribute("rank", turn.rank().to_string())
             .add_attribute("action", "execute_take_turn"))
     }
 
+    // The rules of the car, the actions to buy this turn in order:
+    // - shell the car right in front of us when it is faster than us
+    // - shield ourselves while leading
+    // - accelerate, twice when we are the last car
+    // An action is only bought when it fits in the budget of the turn.
+    pub fn decide(turn: &TurnInfo) -> Vec<(String, u64)> {
+        let mut orders = vec![];
+        let mut budget = turn.me.balance / TURN_BUDGET_DIVISOR;
+        let rank = turn.rank();
+
+        let mut buy = |action: &str, amount: u64| match turn.cost(action, amount) {
+            Some(cost) if cost <= budget => {
+                budget -= cost;
+                orders.push((action.to_string(), amount));
+            }
+            _ => {}
+        };
+
+        if rank > 0 && turn.standings[rank - 1].speed > turn.me.speed {
+            buy(SHELL, 1);
+        }
+
+        if rank == 0 && turn.me.shield == 0 {
+            buy(SHIELD, 1);
+        }
+
+        let last = rank + 1 == turn.standings.len();
+        buy(ACCELERATE, if last { 2 } else { 1 });
+
+        orders
+    }
+
     pub fn execute_ping(
-        deps: DepsMut,
-        env: Env,
-        info: MessageInfo,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
     ) -> Result<Response, ContractError> {
         Ok(Response::new()
             .add_attribute("action", "ping")
-            .add_attribute("response", "pong")
-    )
+            .add_attribute("response", "pong"))
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-    unimplemented!()
+pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {}
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr,
+    };
+    use titans_car_sdk::{
+        testing::{car, order, TurnBuilder},
+        ACCELERATE, SHELL, SHIELD,
+    };
+
+    use super::{execute, execute::decide, instantiate};
+    use crate::{
+        msg::{ExecuteMsg, InstantiateMsg},
+        state::BASE_CAR_ADDR,
+        ContractError,
+    };
+
+    #[test]
+    fn test_instantiate_and_take_turn_sender() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            BASE_CAR_ADDR.load(&deps.storage).unwrap(),
+            Addr::unchecked("base")
+        );
+
+        // Only the base contract can make the car play
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stranger", &[]),
+            ExecuteMsg::TakeTurn {},
+        );
+        assert!(matches!(res, Err(ContractError::Std(_))));
+    }
+
+    #[test]
+    fn test_decide() {
+        // The leader shields itself
+        let standings = vec![car("me", 100, 5), car("other", 90, 8)];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(SHIELD, 1), order(ACCELERATE, 1)]
+        );
+
+        // A faster car right in front of us is shelled
+        let standings = vec![car("first", 200, 8), car("me", 100, 5), car("last", 90, 3)];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(SHELL, 1), order(ACCELERATE, 1)]
+        );
+
+        // The last car accelerates twice
+        let standings = vec![car("first", 200, 3), car("me", 100, 5)];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(ACCELERATE, 2)]
+        );
+    }
+
+    #[test]
+    fn test_decide_budget() {
+        // A twentieth of the balance is spent in one turn, the shell first
+        let standings = vec![car("first", 200, 8), car("me", 100, 5)];
+        let mut turn = TurnBuilder::new("me", standings).build();
+        turn.me.balance = 400;
+        assert_eq!(decide(&turn), vec![order(SHELL, 1)]);
+
+        turn.me.balance = 100;
+        assert!(decide(&turn).is_empty());
+    }
+}
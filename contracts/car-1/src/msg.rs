@@ -6,11 +6,8 @@ pub struct InstantiateMsg {
     pub base_car_addr: Addr,
 }
 
-#[cw_serde]
-pub enum ExecuteMsg {
-    TakeTurn {},
-    Ping {},
-}
+// Cars implement the interface of the SDK
+pub use titans_car_sdk::CarExecuteMsg as ExecuteMsg;
 
 #[cw_serde]
 #[derive(QueryResponses)]
@@ -1,7 +1,7 @@
 pub mod contract;
 mod error;
-pub mod helpers;
 pub mod msg;
 pub mod state;
+pub mod strategy;
 
 pub use crate::error::ContractError;
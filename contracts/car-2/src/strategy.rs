@@ -0,0 +1,121 @@
+use titans_car_sdk::{TurnInfo, ACCELERATE, SHELL, SUPER_SHELL};
+
+// Aggressive accelerator: accelerate as much as possible every turn and shell the leader
+// whenever it is faster than us. The shell is paid first, a quarter of the balance is kept
+// for the shells of the next turns.
+
+// Most accelerations bought in one turn
+pub const MAX_ACCELERATE: u64 = 5;
+
+// Share of the balance kept for the shells, 4 means a quarter
+pub const SHELL_RESERVE_DIVISOR: u64 = 4;
+
+// Actions to buy this turn, in order
+pub fn decide(turn: &TurnInfo) -> Vec<(String, u64)> {
+    let mut orders = vec![];
+    let mut budget = turn.me.balance;
+
+    if let Some(action) = shell_leader(turn) {
+        if let Some(price) = turn.price(action).filter(|price| *price <= budget) {
+            orders.push((action.to_string(), 1));
+            budget -= price;
+        }
+    }
+
+    let reserve = turn.me.balance / SHELL_RESERVE_DIVISOR;
+    let amount = turn.affordable(ACCELERATE, budget.saturating_sub(reserve), MAX_ACCELERATE);
+    if amount > 0 {
+        orders.push((ACCELERATE.to_string(), amount));
+    }
+
+    orders
+}
+
+// The shell that hits the leader, if it is worth it. A shell hits the closest car ahead,
+// so it is only used when the leader is right in front of us, otherwise a super shell
+// hits every car ahead.
+fn shell_leader(turn: &TurnInfo) -> Option<&'static str> {
+    let rank = turn.rank();
+    if rank == 0 {
+        return None;
+    }
+
+    let leader = turn.leader();
+    if leader.speed <= turn.post_sell_speed || leader.speed <= turn.me.speed {
+        return None;
+    }
+
+    if rank == 1 && leader.shield == 0 {
+        return Some(SHELL);
+    }
+    Some(SUPER_SHELL)
+}
+
+#[cfg(test)]
+mod tests {
+    use titans_car_sdk::{
+        testing::{car, order, TurnBuilder},
+        types::CarData,
+        ACCELERATE, SHELL, SUPER_SHELL,
+    };
+
+    use super::{decide, MAX_ACCELERATE};
+
+    #[test]
+    fn test_leader_accelerates() {
+        let turn = TurnBuilder::new("me", vec![car("me", 50, 5), car("other", 40, 8)]).build();
+        assert_eq!(decide(&turn), vec![order(ACCELERATE, MAX_ACCELERATE)]);
+    }
+
+    #[test]
+    fn test_shell_leader_ahead() {
+        let turn = TurnBuilder::new("me", vec![car("leader", 50, 8), car("me", 40, 5)]).build();
+        assert_eq!(
+            decide(&turn),
+            vec![order(SHELL, 1), order(ACCELERATE, MAX_ACCELERATE)]
+        );
+    }
+
+    #[test]
+    fn test_super_shell_leader_far_ahead() {
+        let standings = vec![car("leader", 80, 8), car("second", 60, 2), car("me", 40, 5)];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(SUPER_SHELL, 1), order(ACCELERATE, MAX_ACCELERATE)]
+        );
+
+        // Shields do not protect from super shells
+        let standings = vec![
+            CarData {
+                shield: 2,
+                ..car("leader", 50, 8)
+            },
+            car("me", 40, 5),
+        ];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build())[0],
+            order(SUPER_SHELL, 1)
+        );
+    }
+
+    #[test]
+    fn test_slow_leader_is_not_shelled() {
+        let turn = TurnBuilder::new("me", vec![car("leader", 50, 1), car("me", 40, 5)]).build();
+        assert_eq!(decide(&turn), vec![order(ACCELERATE, MAX_ACCELERATE)]);
+
+        // Not faster than us either
+        let turn = TurnBuilder::new("me", vec![car("leader", 50, 5), car("me", 40, 5)]).build();
+        assert_eq!(decide(&turn), vec![order(ACCELERATE, MAX_ACCELERATE)]);
+    }
+
+    #[test]
+    fn test_budget() {
+        // A quarter of the balance is kept for the shells
+        let mut turn = TurnBuilder::new("me", vec![car("me", 50, 5), car("other", 40, 8)]).build();
+        turn.me.balance = 40;
+        assert_eq!(decide(&turn), vec![order(ACCELERATE, 3)]);
+
+        turn.me.balance = 5;
+        assert!(decide(&turn).is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult}
 
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::BASE_CAR_ADDR;
 
 /*
 // version info for migration info
@@ -14,28 +15,108 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    unimplemented!()
+    let base_car_addr = msg.base_car_addr.clone();
+    BASE_CAR_ADDR.save(deps.storage, &base_car_addr)?;
+    Ok(Response::new()
+        .add_attribute("base_car_addr", base_car_addr)
+        .add_attribute("action", "instantiate"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    _deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
-    _msg: ExecuteMsg,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    unimplemented!()
+    match msg {
+        ExecuteMsg::TakeTurn {} => execute::execute_take_turn(deps, env, info),
+        ExecuteMsg::Ping {} => execute::execute_ping(deps, env, info),
+    }
+}
+
+pub mod execute {
+    use cosmwasm_std::{CosmosMsg, DepsMut, Env, MessageInfo, Response, StdResult};
+    use titans_car_sdk::BaseCarClient;
+
+    use crate::{state::BASE_CAR_ADDR, strategy::decide, ContractError};
+
+    pub fn execute_take_turn(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        base_car.assert_caller(&info)?;
+
+        let turn = base_car.turn_info(&deps.querier, &env.contract.address)?;
+        let orders = decide(&turn);
+        let msgs = orders
+            .iter()
+            .map(|(action, amount)| base_car.buy(action, *amount))
+            .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("contract_addr", env.contract.address.to_string())
+            .add_attribute("orders", orders.len().to_string())
+            .add_attribute("action", "execute_take_turn"))
+    }
+
+    pub fn execute_ping(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        Ok(Response::new()
+            .add_attribute("action", "ping")
+            .add_attribute("response", "pong"))
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-    unimplemented!()
+pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {}
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr,
+    };
+
+    use super::{execute, instantiate};
+    use crate::{
+        msg::{ExecuteMsg, InstantiateMsg},
+        state::BASE_CAR_ADDR,
+        ContractError,
+    };
+
+    #[test]
+    fn test_instantiate_and_take_turn_sender() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            BASE_CAR_ADDR.load(&deps.storage).unwrap(),
+            Addr::unchecked("base")
+        );
+
+        // Only the base contract can make the car play
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stranger", &[]),
+            ExecuteMsg::TakeTurn {},
+        );
+        assert!(matches!(res, Err(ContractError::Std(_))));
+    }
+}
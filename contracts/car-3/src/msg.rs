@@ -1,10 +1,13 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    pub base_car_addr: Addr,
+}
 
-#[cw_serde]
-pub enum ExecuteMsg {}
+// Cars implement the interface of the SDK
+pub use titans_car_sdk::CarExecuteMsg as ExecuteMsg;
 
 #[cw_serde]
 #[derive(QueryResponses)]
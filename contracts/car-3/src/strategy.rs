@@ -0,0 +1,124 @@
+use titans_car_sdk::{TurnInfo, ACCELERATE, BANANA, SHIELD};
+
+// Defensive car: keep a steady speed, protect the lead with bananas and keep a shield
+// while a car is close behind. Each turn spends at most a tenth of the balance.
+
+// Speed the car accelerates to, one at a time
+pub const CRUISE_SPEED: u64 = 10;
+
+// A car closer than this behind us can shell us
+pub const DANGER_DISTANCE: u64 = 50;
+
+// Share of the balance spent in one turn, 10 means a tenth
+pub const TURN_BUDGET_DIVISOR: u64 = 10;
+
+// Actions to buy this turn, in order
+pub fn decide(turn: &TurnInfo) -> Vec<(String, u64)> {
+    let mut orders = vec![];
+    let mut budget = turn.me.balance / TURN_BUDGET_DIVISOR;
+    let mut buy = |action: &str, amount: u64, orders: &mut Vec<(String, u64)>| match turn
+        .cost(action, amount)
+    {
+        Some(cost) if cost <= budget => {
+            budget -= cost;
+            orders.push((action.to_string(), amount));
+        }
+        _ => {}
+    };
+
+    if let Some(behind) = turn.next_behind() {
+        let distance = turn.me.y - behind.y;
+
+        // Nothing protects from a car on the same position
+        if distance > 0 && distance <= DANGER_DISTANCE && turn.me.shield == 0 {
+            buy(SHIELD, 1, &mut orders);
+        }
+
+        // A banana stops the cars behind the leader
+        if turn.rank() == 0 && distance > 0 {
+            buy(BANANA, 1, &mut orders);
+        }
+    }
+
+    if turn.me.speed < CRUISE_SPEED {
+        buy(ACCELERATE, 1, &mut orders);
+    }
+
+    orders
+}
+
+#[cfg(test)]
+mod tests {
+    use titans_car_sdk::{
+        testing::{car, order, TurnBuilder},
+        types::CarData,
+        ACCELERATE, BANANA, SHIELD,
+    };
+
+    use super::{decide, CRUISE_SPEED, DANGER_DISTANCE};
+
+    #[test]
+    fn test_leader_protects_the_lead() {
+        let standings = vec![car("me", 100, 5), car("other", 100 - DANGER_DISTANCE, 8)];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(SHIELD, 1), order(BANANA, 1), order(ACCELERATE, 1)]
+        );
+
+        // Far enough, or already shielded, no shield is bought
+        let standings = vec![car("me", 100, 5), car("other", 10, 8)];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(BANANA, 1), order(ACCELERATE, 1)]
+        );
+        let standings = vec![
+            CarData {
+                shield: 1,
+                ..car("me", 100, 5)
+            },
+            car("other", 90, 8),
+        ];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(BANANA, 1), order(ACCELERATE, 1)]
+        );
+    }
+
+    #[test]
+    fn test_chaser_keeps_a_shield() {
+        let standings = vec![
+            car("leader", 200, 8),
+            car("me", 100, 5),
+            car("other", 80, 8),
+        ];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(SHIELD, 1), order(ACCELERATE, 1)]
+        );
+
+        // The last car has nobody to fear
+        let standings = vec![car("leader", 200, 8), car("me", 100, 5)];
+        assert_eq!(
+            decide(&TurnBuilder::new("me", standings).build()),
+            vec![order(ACCELERATE, 1)]
+        );
+    }
+
+    #[test]
+    fn test_cruise_speed() {
+        let standings = vec![car("leader", 200, 8), car("me", 100, CRUISE_SPEED)];
+        assert!(decide(&TurnBuilder::new("me", standings).build()).is_empty());
+    }
+
+    #[test]
+    fn test_budget() {
+        // A tenth of the balance is spent in one turn
+        let standings = vec![car("me", 100, 5), car("other", 90, 8)];
+        let mut turn = TurnBuilder::new("me", standings).build();
+        turn.me.balance = 200;
+        assert_eq!(decide(&turn), vec![order(SHIELD, 1), order(BANANA, 1)]);
+
+        turn.me.balance = 99;
+        assert!(decide(&turn).is_empty());
+    }
+}
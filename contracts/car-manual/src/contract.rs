@@ -0,0 +1,416 @@
+use std::collections::BTreeMap;
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+};
+use titans_car_sdk::BANANA;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, PlanResponse, QueryMsg};
+use crate::state::{BASE_CAR_ADDR, FALLBACK, OWNER, QUEUE};
+
+// Bound on the actions bought in a turn
+pub const MAX_ACTIONS: usize = 8;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    validate_actions(&msg.fallback)?;
+    let base_car_addr = msg.base_car_addr.clone();
+    BASE_CAR_ADDR.save(deps.storage, &base_car_addr)?;
+    OWNER.save(deps.storage, &info.sender)?;
+    FALLBACK.save(deps.storage, &msg.fallback)?;
+    Ok(Response::new()
+        .add_attribute("base_car_addr", base_car_addr)
+        .add_attribute("owner", info.sender)
+        .add_attribute("action", "instantiate"))
+}
+
+// The base contract would reject the Buy of the actions, and the whole turn with it
+fn validate_actions(actions: &[(String, u64)]) -> Result<(), ContractError> {
+    if actions.len() > MAX_ACTIONS {
+        return Err(ContractError::TooManyActions { max: MAX_ACTIONS });
+    }
+    for (action, amount) in actions {
+        if *amount == 0 || (action == BANANA && *amount > 1) {
+            return Err(ContractError::InvalidAmount {
+                action: action.clone(),
+                amount: *amount,
+            });
+        }
+    }
+    total_amounts(actions)?;
+    Ok(())
+}
+
+// The amounts of an action listed twice add up
+fn total_amounts(actions: &[(String, u64)]) -> Result<BTreeMap<&str, u64>, ContractError> {
+    let mut amounts: BTreeMap<&str, u64> = BTreeMap::new();
+    for (action, amount) in actions {
+        let total = amounts.entry(action).or_default();
+        *total = total
+            .checked_add(*amount)
+            .ok_or_else(|| ContractError::AmountOverflow {
+                action: action.clone(),
+            })?;
+    }
+    Ok(amounts)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::TakeTurn {} => execute::execute_take_turn(deps, env, info),
+        ExecuteMsg::Ping {} => execute::execute_ping(deps, env, info),
+        ExecuteMsg::QueueActions { turn, actions } => {
+            execute::execute_queue_actions(deps, env, info, turn, actions)
+        }
+        ExecuteMsg::UnqueueActions { turn } => {
+            execute::execute_unqueue_actions(deps, env, info, turn)
+        }
+        ExecuteMsg::ClearQueue {} => execute::execute_clear_queue(deps, env, info),
+        ExecuteMsg::SetFallback { actions } => {
+            execute::execute_set_fallback(deps, env, info, actions)
+        }
+    }
+}
+
+pub mod execute {
+    use cosmwasm_std::{CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+    use titans_car_sdk::{types::State, BaseCarClient, TurnInfo};
+
+    use super::{total_amounts, validate_actions};
+    use crate::{
+        state::{BASE_CAR_ADDR, FALLBACK, OWNER, QUEUE},
+        ContractError,
+    };
+
+    // The actions are enabled in the race and the balance pays all of them
+    fn affordable(turn: &TurnInfo, actions: &[(String, u64)]) -> bool {
+        let Ok(amounts) = total_amounts(actions) else {
+            return false;
+        };
+
+        let mut cost: u64 = 0;
+        for (action, amount) in amounts {
+            let Some(action_cost) = turn.cost(action, amount) else {
+                return false;
+            };
+            cost = cost.saturating_add(action_cost);
+        }
+        cost <= turn.me.balance
+    }
+
+    fn assert_owner(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(())
+    }
+
+    pub fn execute_take_turn(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        base_car.assert_caller(&info)?;
+
+        let turn = base_car.turn_info(&deps.querier, &env.contract.address)?;
+        let queued = QUEUE.may_load(deps.storage, turn.turns)?;
+        QUEUE.remove(deps.storage, turn.turns);
+
+        // Queued actions the car can not pay fall back too, so the turn is not lost
+        let (plan, actions) = match queued {
+            Some(actions) if affordable(&turn, &actions) => ("queued", actions),
+            _ => {
+                let fallback = FALLBACK.load(deps.storage)?;
+                if affordable(&turn, &fallback) {
+                    ("fallback", fallback)
+                } else {
+                    ("none", vec![])
+                }
+            }
+        };
+        let msgs = actions
+            .iter()
+            .map(|(action, amount)| base_car.buy(action, *amount))
+            .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("contract_addr", env.contract.address.to_string())
+            .add_attribute("plan", plan)
+            .add_attribute("orders", actions.len().to_string())
+            .add_attribute("action", "execute_take_turn"))
+    }
+
+    pub fn execute_ping(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        Ok(Response::new()
+            .add_attribute("action", "ping")
+            .add_attribute("response", "pong"))
+    }
+
+    pub fn execute_queue_actions(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        turn: u64,
+        actions: Vec<(String, u64)>,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.as_ref(), &info)?;
+        validate_actions(&actions)?;
+
+        // Turns of the next race can be queued while the last one is over
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        let game = base_car.game_state(&deps.querier)?;
+        if game.state == State::Active && turn < game.turns {
+            return Err(ContractError::TurnPlayed { turn });
+        }
+
+        QUEUE.save(deps.storage, turn, &actions)?;
+        Ok(Response::new()
+            .add_attribute("turn", turn.to_string())
+            .add_attribute("orders", actions.len().to_string())
+            .add_attribute("action", "queue_actions"))
+    }
+
+    pub fn execute_unqueue_actions(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        turn: u64,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.as_ref(), &info)?;
+        QUEUE.remove(deps.storage, turn);
+        Ok(Response::new()
+            .add_attribute("turn", turn.to_string())
+            .add_attribute("action", "unqueue_actions"))
+    }
+
+    pub fn execute_clear_queue(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.as_ref(), &info)?;
+        QUEUE.clear(deps.storage);
+        Ok(Response::new().add_attribute("action", "clear_queue"))
+    }
+
+    pub fn execute_set_fallback(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        actions: Vec<(String, u64)>,
+    ) -> Result<Response, ContractError> {
+        assert_owner(deps.as_ref(), &info)?;
+        validate_actions(&actions)?;
+        FALLBACK.save(deps.storage, &actions)?;
+        Ok(Response::new().add_attribute("action", "set_fallback"))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetPlan {} => to_binary(&PlanResponse {
+            owner: OWNER.load(deps.storage)?,
+            fallback: FALLBACK.load(deps.storage)?,
+            queue: QUEUE
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr,
+    };
+    use titans_car_sdk::{testing::mock_base_car, types::State, BANANA};
+
+    use super::{execute, instantiate, query, MAX_ACTIONS};
+    use crate::{
+        msg::{ExecuteMsg, InstantiateMsg, PlanResponse, QueryMsg},
+        ContractError,
+    };
+
+    fn accelerate(amount: u64) -> Vec<(String, u64)> {
+        vec![("accelerate".to_string(), amount)]
+    }
+
+    #[test]
+    fn test_queue_actions() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            fallback: accelerate(1),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        mock_base_car(&mut deps.querier, State::Active, 4, vec![]);
+
+        let queue = |turn, actions| ExecuteMsg::QueueActions { turn, actions };
+
+        // Only the owner drives the car
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stranger", &[]),
+            queue(6, accelerate(2)),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        // Not a turn already played
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            queue(3, accelerate(2)),
+        );
+        assert!(matches!(res, Err(ContractError::TurnPlayed { turn: 3 })));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            queue(6, vec![("accelerate".to_string(), 1); MAX_ACTIONS + 1]),
+        );
+        assert!(matches!(res, Err(ContractError::TooManyActions { .. })));
+
+        // Queueing a turn again replaces its actions
+        for (turn, amount) in [(8, 3), (6, 2), (8, 4), (10, 5)] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("owner", &[]),
+                queue(turn, accelerate(amount)),
+            )
+            .unwrap();
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::UnqueueActions { turn: 10 },
+        )
+        .unwrap();
+
+        let res: PlanResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetPlan {}).unwrap()).unwrap();
+        assert_eq!(res.owner, Addr::unchecked("owner"));
+        assert_eq!(res.fallback, accelerate(1));
+        assert_eq!(res.queue, vec![(6, accelerate(2)), (8, accelerate(4))]);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::ClearQueue {},
+        )
+        .unwrap();
+        let res: PlanResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetPlan {}).unwrap()).unwrap();
+        assert!(res.queue.is_empty());
+    }
+
+    #[test]
+    fn test_queue_invalid_actions() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            fallback: accelerate(0),
+        };
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg);
+        assert!(matches!(
+            res,
+            Err(ContractError::InvalidAmount { amount: 0, .. })
+        ));
+
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            fallback: accelerate(1),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        mock_base_car(&mut deps.querier, State::Active, 4, vec![]);
+
+        let mut queue = |actions| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("owner", &[]),
+                ExecuteMsg::QueueActions { turn: 6, actions },
+            )
+        };
+
+        // The base contract would reject the Buy and the car would lose the turn
+        let res = queue(accelerate(0));
+        assert!(matches!(
+            res,
+            Err(ContractError::InvalidAmount { amount: 0, .. })
+        ));
+        let res = queue(vec![(BANANA.to_string(), 2)]);
+        assert!(matches!(
+            res,
+            Err(ContractError::InvalidAmount { amount: 2, .. })
+        ));
+        let res = queue([accelerate(u64::MAX), accelerate(1)].concat());
+        assert!(matches!(res, Err(ContractError::AmountOverflow { .. })));
+        queue(vec![(BANANA.to_string(), 1)]).unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::SetFallback {
+                actions: vec![(BANANA.to_string(), 3)],
+            },
+        );
+        assert!(matches!(
+            res,
+            Err(ContractError::InvalidAmount { amount: 3, .. })
+        ));
+
+        let res: PlanResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetPlan {}).unwrap()).unwrap();
+        assert_eq!(res.fallback, accelerate(1));
+        assert_eq!(res.queue, vec![(6, vec![(BANANA.to_string(), 1)])]);
+    }
+
+    #[test]
+    fn test_take_turn_sender() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            fallback: vec![],
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        // Only the base contract can make the car play
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::TakeTurn {},
+        );
+        assert!(matches!(res, Err(ContractError::Std(_))));
+    }
+}
@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Turn {turn} is already played")]
+    TurnPlayed { turn: u64 },
+
+    #[error("At most {max} actions per turn")]
+    TooManyActions { max: usize },
+
+    #[error("Can not buy {amount} {action} in a turn")]
+    InvalidAmount { action: String, amount: u64 },
+
+    #[error("Amount of {action} overflows")]
+    AmountOverflow { action: String },
+}
@@ -0,0 +1,46 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+
+// Actions are (action, amount) pairs, bought in order
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub base_car_addr: Addr,
+    pub fallback: Vec<(String, u64)>,
+}
+
+// The car interface of the SDK, and the plan of the owner
+#[cw_serde]
+pub enum ExecuteMsg {
+    TakeTurn {},
+    Ping {},
+
+    // Owner only: buy the actions when the base contract plays `turn`, the turns counter of
+    // GetGameState, replaces what was queued for that turn. Empty actions skip the turn.
+    QueueActions {
+        turn: u64,
+        actions: Vec<(String, u64)>,
+    },
+    // Owner only
+    UnqueueActions {
+        turn: u64,
+    },
+    ClearQueue {},
+    SetFallback {
+        actions: Vec<(String, u64)>,
+    },
+}
+
+#[cw_serde]
+pub struct PlanResponse {
+    pub owner: Addr,
+    pub fallback: Vec<(String, u64)>,
+    // By turn, not played yet
+    pub queue: Vec<(u64, Vec<(String, u64)>)>,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(PlanResponse)]
+    GetPlan {},
+}
@@ -0,0 +1,13 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+
+pub const BASE_CAR_ADDR: Item<Addr> = Item::new("base_car_addr");
+
+// OWNER is the instantiator of the car, the one driving it
+pub const OWNER: Item<Addr> = Item::new("owner");
+
+// Actions bought when nothing is queued for the turn
+pub const FALLBACK: Item<Vec<(String, u64)>> = Item::new("fallback");
+
+// Actions queued by the owner, by turn of the base contract
+pub const QUEUE: Map<u64, Vec<(String, u64)>> = Map::new("queue");
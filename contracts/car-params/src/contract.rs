@@ -0,0 +1,213 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, ParamsResponse, QueryMsg};
+use crate::state::{ADMIN, BASE_CAR_ADDR, PARAMS};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let base_car_addr = msg.base_car_addr.clone();
+    BASE_CAR_ADDR.save(deps.storage, &base_car_addr)?;
+    ADMIN.save(deps.storage, &info.sender)?;
+    PARAMS.save(deps.storage, &msg.params)?;
+    Ok(Response::new()
+        .add_attribute("base_car_addr", base_car_addr)
+        .add_attribute("admin", info.sender)
+        .add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::TakeTurn {} => execute::execute_take_turn(deps, env, info),
+        ExecuteMsg::Ping {} => execute::execute_ping(deps, env, info),
+        ExecuteMsg::UpdateParams { params } => {
+            execute::execute_update_params(deps, env, info, params)
+        }
+    }
+}
+
+pub mod execute {
+    use cosmwasm_std::{CosmosMsg, DepsMut, Env, MessageInfo, Response, StdResult};
+    use titans_car_sdk::{types::State, BaseCarClient, StrategyParams};
+
+    use crate::{
+        state::{ADMIN, BASE_CAR_ADDR, PARAMS},
+        ContractError,
+    };
+
+    pub fn execute_take_turn(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        base_car.assert_caller(&info)?;
+
+        let turn = base_car.turn_info(&deps.querier, &env.contract.address)?;
+        let orders = PARAMS.load(deps.storage)?.decide(&turn);
+        let msgs = orders
+            .iter()
+            .map(|(action, amount)| base_car.buy(action, *amount))
+            .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("contract_addr", env.contract.address.to_string())
+            .add_attribute("orders", orders.len().to_string())
+            .add_attribute("action", "execute_take_turn"))
+    }
+
+    pub fn execute_ping(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        Ok(Response::new()
+            .add_attribute("action", "ping")
+            .add_attribute("response", "pong"))
+    }
+
+    pub fn execute_update_params(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        params: StrategyParams,
+    ) -> Result<Response, ContractError> {
+        if info.sender != ADMIN.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // The car is racing while it is registered in the active race of the base contract
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        if base_car.game_state(&deps.querier)?.state == State::Active
+            && base_car
+                .car_data(&deps.querier, &env.contract.address)?
+                .is_some()
+        {
+            return Err(ContractError::Racing);
+        }
+
+        PARAMS.save(deps.storage, &params)?;
+        Ok(Response::new().add_attribute("action", "update_params"))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetParams {} => to_binary(&ParamsResponse {
+            admin: ADMIN.load(deps.storage)?,
+            params: PARAMS.load(deps.storage)?,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr,
+    };
+    use titans_car_sdk::{testing::mock_base_car, types::State, StrategyParams};
+
+    use super::{execute, instantiate, query};
+    use crate::{
+        msg::{ExecuteMsg, InstantiateMsg, ParamsResponse, QueryMsg},
+        ContractError,
+    };
+
+    #[test]
+    fn test_update_params() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            params: StrategyParams::default(),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+        let params = StrategyParams {
+            target_speed: 30,
+            ..StrategyParams::default()
+        };
+        let update = ExecuteMsg::UpdateParams {
+            params: params.clone(),
+        };
+
+        // Only the admin can update the params
+        mock_base_car(&mut deps.querier, State::Waiting, 0, vec![]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stranger", &[]),
+            update.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        // Not while the car is racing
+        let car = mock_env().contract.address;
+        mock_base_car(&mut deps.querier, State::Active, 0, vec![car.clone()]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            update.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Racing)));
+
+        // A race without the car, or a finished race
+        mock_base_car(
+            &mut deps.querier,
+            State::Active,
+            0,
+            vec![Addr::unchecked("other")],
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            update.clone(),
+        )
+        .unwrap();
+        mock_base_car(&mut deps.querier, State::Done, 0, vec![car]);
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), update).unwrap();
+
+        let res: ParamsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetParams {}).unwrap())
+                .unwrap();
+        assert_eq!(res.admin, Addr::unchecked("admin"));
+        assert_eq!(res.params, params);
+    }
+
+    #[test]
+    fn test_take_turn_sender() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            params: StrategyParams::default(),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+        // Only the base contract can make the car play
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::TakeTurn {},
+        );
+        assert!(matches!(res, Err(ContractError::Std(_))));
+    }
+}
@@ -0,0 +1,29 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+use titans_car_sdk::StrategyParams;
+
+// The parameters exported by titans-evolve are an InstantiateMsg of this car
+pub use titans_car_sdk::params::ParamsCarInstantiateMsg as InstantiateMsg;
+
+// The car interface of the SDK, and the params update
+#[cw_serde]
+pub enum ExecuteMsg {
+    TakeTurn {},
+    Ping {},
+
+    // Admin only: the params of the next races, can not change while the car is racing
+    UpdateParams { params: StrategyParams },
+}
+
+#[cw_serde]
+pub struct ParamsResponse {
+    pub admin: Addr,
+    pub params: StrategyParams,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ParamsResponse)]
+    GetParams {},
+}
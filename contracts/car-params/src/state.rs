@@ -0,0 +1,10 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+use titans_car_sdk::StrategyParams;
+
+pub const BASE_CAR_ADDR: Item<Addr> = Item::new("base_car_addr");
+
+// ADMIN is the instantiator of the car, the only one allowed to update the params
+pub const ADMIN: Item<Addr> = Item::new("admin");
+
+pub const PARAMS: Item<StrategyParams> = Item::new("params");
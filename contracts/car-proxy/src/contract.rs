@@ -0,0 +1,258 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult,
+};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, ProxyResponse, QueryMsg};
+use crate::state::{BASE_CAR_ADDR, OWNER, STRATEGY};
+
+// Reply of the TakeTurn sub message sent to the strategy
+pub const STRATEGY_REPLY_ID: u64 = 1;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let base_car_addr = msg.base_car_addr.clone();
+    BASE_CAR_ADDR.save(deps.storage, &base_car_addr)?;
+    OWNER.save(deps.storage, &info.sender)?;
+    STRATEGY.save(deps.storage, &msg.strategy)?;
+    Ok(Response::new()
+        .add_attribute("base_car_addr", base_car_addr)
+        .add_attribute("owner", info.sender)
+        .add_attribute("strategy", msg.strategy)
+        .add_attribute("action", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::TakeTurn {} => execute::execute_take_turn(deps, env, info),
+        ExecuteMsg::Ping {} => execute::execute_ping(deps, env, info),
+        ExecuteMsg::SetStrategy { strategy } => {
+            execute::execute_set_strategy(deps, env, info, strategy)
+        }
+    }
+}
+
+pub mod execute {
+    use cosmwasm_std::{to_binary, Addr, DepsMut, Env, MessageInfo, Response, SubMsg, WasmMsg};
+    use titans_car_sdk::{types::State, BaseCarClient, StrategyExecuteMsg};
+
+    use super::STRATEGY_REPLY_ID;
+    use crate::{
+        state::{BASE_CAR_ADDR, OWNER, STRATEGY},
+        ContractError,
+    };
+
+    // The strategy decides on the turn of the proxy, its orders are bought by the proxy in
+    // the reply: the base contract knows the cars by the sender of the Buy messages
+    pub fn execute_take_turn(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        base_car.assert_caller(&info)?;
+
+        let turn = base_car.turn_info(&deps.querier, &env.contract.address)?;
+        let strategy = STRATEGY.load(deps.storage)?;
+        let msg = WasmMsg::Execute {
+            contract_addr: strategy.to_string(),
+            msg: to_binary(&StrategyExecuteMsg::TakeTurn { turn })?,
+            funds: vec![],
+        };
+
+        Ok(Response::new()
+            .add_submessage(SubMsg::reply_on_success(msg, STRATEGY_REPLY_ID))
+            .add_attribute("contract_addr", env.contract.address.to_string())
+            .add_attribute("strategy", strategy)
+            .add_attribute("action", "execute_take_turn"))
+    }
+
+    pub fn execute_ping(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        Ok(Response::new()
+            .add_attribute("action", "ping")
+            .add_attribute("response", "pong"))
+    }
+
+    pub fn execute_set_strategy(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        strategy: Addr,
+    ) -> Result<Response, ContractError> {
+        if info.sender != OWNER.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        // The car is racing while it is registered in the active race of the base contract
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        if base_car.game_state(&deps.querier)?.state == State::Active
+            && base_car
+                .car_data(&deps.querier, &env.contract.address)?
+                .is_some()
+        {
+            return Err(ContractError::Racing);
+        }
+
+        STRATEGY.save(deps.storage, &strategy)?;
+        Ok(Response::new()
+            .add_attribute("strategy", strategy)
+            .add_attribute("action", "set_strategy"))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        STRATEGY_REPLY_ID => reply::strategy_reply(deps, env, msg),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+pub mod reply {
+    use cosmwasm_std::{from_binary, CosmosMsg, DepsMut, Env, Reply, Response, StdResult};
+    use cw_utils::parse_reply_execute_data;
+    use titans_car_sdk::{BaseCarClient, StrategyResponse};
+
+    use crate::{state::BASE_CAR_ADDR, ContractError};
+
+    pub fn strategy_reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+        let data = parse_reply_execute_data(msg)?
+            .data
+            .ok_or(ContractError::NoStrategyResponse)?;
+        let res: StrategyResponse = from_binary(&data)?;
+
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        let msgs = res
+            .orders
+            .iter()
+            .map(|(action, amount)| base_car.buy(action, *amount))
+            .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("orders", res.orders.len().to_string())
+            .add_attribute("action", "strategy_reply"))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetProxy {} => to_binary(&ProxyResponse {
+            owner: OWNER.load(deps.storage)?,
+            strategy: STRATEGY.load(deps.storage)?,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        Addr, Empty, OwnedDeps, Reply, SubMsgResponse, SubMsgResult,
+    };
+    use titans_car_sdk::{testing::mock_base_car, types::State};
+
+    use super::{execute, instantiate, query, reply, STRATEGY_REPLY_ID};
+    use crate::{
+        msg::{ExecuteMsg, InstantiateMsg, ProxyResponse, QueryMsg},
+        ContractError,
+    };
+
+    fn instantiate_proxy(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>) {
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            strategy: Addr::unchecked("strategy"),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn test_set_strategy() {
+        let mut deps = mock_dependencies();
+        instantiate_proxy(&mut deps);
+        let set = ExecuteMsg::SetStrategy {
+            strategy: Addr::unchecked("strategy_v2"),
+        };
+
+        // Only the owner can swap the strategy
+        mock_base_car(&mut deps.querier, State::Waiting, 0, vec![]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stranger", &[]),
+            set.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        // Not while the car is racing
+        let car = mock_env().contract.address;
+        mock_base_car(&mut deps.querier, State::Active, 0, vec![car.clone()]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            set.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Racing)));
+
+        mock_base_car(&mut deps.querier, State::Done, 0, vec![car]);
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), set).unwrap();
+
+        let res: ProxyResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetProxy {}).unwrap()).unwrap();
+        assert_eq!(res.owner, Addr::unchecked("owner"));
+        assert_eq!(res.strategy, Addr::unchecked("strategy_v2"));
+    }
+
+    #[test]
+    fn test_take_turn_sender() {
+        let mut deps = mock_dependencies();
+        instantiate_proxy(&mut deps);
+
+        // Only the base contract can make the car play, not the owner nor the strategy
+        for sender in ["owner", "strategy"] {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::TakeTurn {},
+            );
+            assert!(matches!(res, Err(ContractError::Std(_))));
+        }
+    }
+
+    #[test]
+    fn test_strategy_without_response() {
+        let mut deps = mock_dependencies();
+        instantiate_proxy(&mut deps);
+
+        let msg = Reply {
+            id: STRATEGY_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        let res = reply(deps.as_mut(), mock_env(), msg);
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use cosmwasm_std::StdError;
+use cw_utils::ParseReplyError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("The strategy can not change while the car is racing")]
+    Racing,
+
+    #[error("The strategy answered without orders")]
+    NoStrategyResponse,
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+}
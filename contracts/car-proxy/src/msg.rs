@@ -0,0 +1,31 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub base_car_addr: Addr,
+    pub strategy: Addr,
+}
+
+// The car interface of the SDK, and the strategy swap
+#[cw_serde]
+pub enum ExecuteMsg {
+    TakeTurn {},
+    Ping {},
+
+    // Owner only: play with another strategy contract, can not change while the car is racing
+    SetStrategy { strategy: Addr },
+}
+
+#[cw_serde]
+pub struct ProxyResponse {
+    pub owner: Addr,
+    pub strategy: Addr,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(ProxyResponse)]
+    GetProxy {},
+}
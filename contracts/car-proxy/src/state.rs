@@ -0,0 +1,10 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+
+pub const BASE_CAR_ADDR: Item<Addr> = Item::new("base_car_addr");
+
+// OWNER is the instantiator of the car, the only one allowed to swap the strategy
+pub const OWNER: Item<Addr> = Item::new("owner");
+
+// Contract deciding the turns of the car, see `StrategyExecuteMsg`
+pub const STRATEGY: Item<Addr> = Item::new("strategy");
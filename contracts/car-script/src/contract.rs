@@ -0,0 +1,358 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+};
+
+use crate::error::ContractError;
+use crate::msg::{DryRunResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RulesResponse};
+use crate::script::{evaluate, validate, Rule};
+use crate::state::{ADMIN, BASE_CAR_ADDR, RULES};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    assert_valid_rules(&msg.rules)?;
+    let base_car_addr = msg.base_car_addr.clone();
+    BASE_CAR_ADDR.save(deps.storage, &base_car_addr)?;
+    ADMIN.save(deps.storage, &info.sender)?;
+    RULES.save(deps.storage, &msg.rules)?;
+    Ok(Response::new()
+        .add_attribute("base_car_addr", base_car_addr)
+        .add_attribute("admin", info.sender)
+        .add_attribute("rules", msg.rules.len().to_string())
+        .add_attribute("action", "instantiate"))
+}
+
+fn assert_valid_rules(rules: &[Rule]) -> Result<(), ContractError> {
+    match validate(rules) {
+        Some(reason) => Err(ContractError::InvalidRules { reason }),
+        None => Ok(()),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::TakeTurn {} => execute::execute_take_turn(deps, env, info),
+        ExecuteMsg::Ping {} => execute::execute_ping(deps, env, info),
+        ExecuteMsg::SetRules { rules } => execute::execute_set_rules(deps, env, info, rules),
+    }
+}
+
+pub mod execute {
+    use cosmwasm_std::{CosmosMsg, DepsMut, Env, MessageInfo, Response, StdResult};
+    use titans_car_sdk::{types::State, BaseCarClient};
+
+    use super::assert_valid_rules;
+    use crate::{
+        script::{evaluate, Rule},
+        state::{ADMIN, BASE_CAR_ADDR, RULES},
+        ContractError,
+    };
+
+    pub fn execute_take_turn(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        base_car.assert_caller(&info)?;
+
+        let turn = base_car.turn_info(&deps.querier, &env.contract.address)?;
+        let evaluation = evaluate(&RULES.load(deps.storage)?, &turn);
+        let msgs = evaluation
+            .orders
+            .iter()
+            .map(|order| base_car.buy(&order.action, order.amount))
+            .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("contract_addr", env.contract.address.to_string())
+            .add_attribute("matched", evaluation.matched.len().to_string())
+            .add_attribute("orders", evaluation.orders.len().to_string())
+            .add_attribute("action", "execute_take_turn"))
+    }
+
+    pub fn execute_ping(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        Ok(Response::new()
+            .add_attribute("action", "ping")
+            .add_attribute("response", "pong"))
+    }
+
+    pub fn execute_set_rules(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        rules: Vec<Rule>,
+    ) -> Result<Response, ContractError> {
+        if info.sender != ADMIN.load(deps.storage)? {
+            return Err(ContractError::Unauthorized {});
+        }
+        assert_valid_rules(&rules)?;
+
+        // The car is racing while it is registered in the active race of the base contract
+        let base_car = BaseCarClient(BASE_CAR_ADDR.load(deps.storage)?);
+        if base_car.game_state(&deps.querier)?.state == State::Active
+            && base_car
+                .car_data(&deps.querier, &env.contract.address)?
+                .is_some()
+        {
+            return Err(ContractError::Racing);
+        }
+
+        RULES.save(deps.storage, &rules)?;
+        Ok(Response::new()
+            .add_attribute("rules", rules.len().to_string())
+            .add_attribute("action", "set_rules"))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetRules {} => to_binary(&RulesResponse {
+            admin: ADMIN.load(deps.storage)?,
+            rules: RULES.load(deps.storage)?,
+        }),
+        QueryMsg::DryRun { turn, rules } => {
+            // The conditions compare the car to the standings
+            if turn.standings.is_empty() {
+                return Err(StdError::generic_err("The turn snapshot has no standings"));
+            }
+
+            let rules = match rules {
+                Some(rules) => rules,
+                None => RULES.load(deps.storage)?,
+            };
+
+            let mut errors: Vec<String> = validate(&rules).into_iter().collect();
+            for (index, rule) in rules.iter().enumerate() {
+                for order in &rule.then {
+                    if turn.price(&order.action).is_none() {
+                        errors.push(format!(
+                            "rule {index} buys {}, not enabled in the race",
+                            order.action
+                        ));
+                    }
+                }
+            }
+
+            let evaluation = evaluate(&rules, &turn);
+            to_binary(&DryRunResponse {
+                errors,
+                matched: evaluation.matched,
+                orders: evaluation.orders,
+                skipped: evaluation.skipped,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Addr,
+    };
+    use titans_car_sdk::{
+        testing::mock_base_car,
+        types::{Config, State},
+        TurnInfo,
+    };
+    use titans_engine::{Race, ACCELERATE};
+
+    use super::{execute, instantiate, query};
+    use crate::{
+        msg::{DryRunResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RulesResponse},
+        script::{Cmp, Condition, Order, Rule, Subject},
+        ContractError,
+    };
+
+    fn accelerate_below(speed: u64, amount: u64) -> Rule {
+        Rule {
+            when: vec![Condition {
+                subject: Subject::Speed,
+                op: Cmp::Lt,
+                value: speed,
+            }],
+            then: vec![Order {
+                action: ACCELERATE.to_string(),
+                amount,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_set_rules() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            rules: vec![accelerate_below(10, 1)],
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+        let rules = vec![accelerate_below(20, 2)];
+        let set = ExecuteMsg::SetRules {
+            rules: rules.clone(),
+        };
+
+        // Only the admin can replace the rules
+        mock_base_car(&mut deps.querier, State::Waiting, 0, vec![]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("stranger", &[]),
+            set.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Unauthorized {})));
+
+        // Rules are validated
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetRules {
+                rules: vec![accelerate_below(20, 0)],
+            },
+        );
+        assert!(matches!(res, Err(ContractError::InvalidRules { .. })));
+
+        // Not while the car is racing
+        let car = mock_env().contract.address;
+        mock_base_car(&mut deps.querier, State::Active, 0, vec![car.clone()]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            set.clone(),
+        );
+        assert!(matches!(res, Err(ContractError::Racing)));
+
+        // Between races
+        mock_base_car(&mut deps.querier, State::Done, 0, vec![car]);
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), set).unwrap();
+
+        let res: RulesResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetRules {}).unwrap()).unwrap();
+        assert_eq!(res.admin, Addr::unchecked("admin"));
+        assert_eq!(res.rules, rules);
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            rules: vec![accelerate_below(10, 3)],
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+        // Start of a default race
+        let race = Race::new(Config::default().rules(), &["me", "other"]);
+        let turn = TurnInfo::from_race(&race, "me").unwrap();
+
+        // The stored rules
+        let res: DryRunResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::DryRun {
+                    turn: turn.clone(),
+                    rules: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(res.errors.is_empty());
+        assert_eq!(res.matched, vec![0]);
+        assert_eq!(res.orders.len(), 1);
+
+        // Candidate rules, with an order of an unknown action and one of a zero amount
+        let mut rule = accelerate_below(10, 0);
+        rule.then.push(Order {
+            action: "nitro".to_string(),
+            amount: 1,
+        });
+        let res: DryRunResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::DryRun {
+                    turn: turn.clone(),
+                    rules: Some(vec![rule, accelerate_below(0, 1)]),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.errors.len(), 2);
+        assert_eq!(res.matched, vec![0]);
+        assert_eq!(res.skipped.len(), 1);
+
+        // An amount over the bound is reported and not priced
+        let res: DryRunResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::DryRun {
+                    turn: turn.clone(),
+                    rules: Some(vec![accelerate_below(10, u64::MAX)]),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.errors.len(), 1);
+        assert_eq!(res.skipped.len(), 1);
+
+        // A snapshot without standings is rejected
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::DryRun {
+                turn: TurnInfo {
+                    standings: vec![],
+                    ..turn
+                },
+                rules: None,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_take_turn_sender() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            base_car_addr: Addr::unchecked("base"),
+            rules: vec![],
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+        // Only the base contract can make the car play
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            ExecuteMsg::TakeTurn {},
+        );
+        assert!(matches!(res, Err(ContractError::Std(_))));
+    }
+}
@@ -0,0 +1,17 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Rules can not change while the car is racing")]
+    Racing,
+
+    #[error("Invalid rules: {reason}")]
+    InvalidRules { reason: String },
+}
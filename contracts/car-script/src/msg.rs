@@ -0,0 +1,53 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+use titans_car_sdk::TurnInfo;
+
+use crate::script::{Order, Rule};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub base_car_addr: Addr,
+    pub rules: Vec<Rule>,
+}
+
+// The car interface of the SDK, and the rules update
+#[cw_serde]
+pub enum ExecuteMsg {
+    TakeTurn {},
+    Ping {},
+
+    // Admin only: replace the rules, can not change while the car is racing
+    SetRules { rules: Vec<Rule> },
+}
+
+#[cw_serde]
+pub struct RulesResponse {
+    pub admin: Addr,
+    pub rules: Vec<Rule>,
+}
+
+#[cw_serde]
+pub struct DryRunResponse {
+    // Why the rules can not be saved, or refer to actions missing from the snapshot
+    pub errors: Vec<String>,
+    // Index of the rules whose conditions hold
+    pub matched: Vec<u64>,
+    // Orders the car would buy, in order
+    pub orders: Vec<Order>,
+    // Orders of the matched rules over the balance or not enabled
+    pub skipped: Vec<Order>,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(RulesResponse)]
+    GetRules {},
+
+    // Evaluate the rules, the stored ones by default, against a turn snapshot
+    #[returns(DryRunResponse)]
+    DryRun {
+        turn: TurnInfo,
+        rules: Option<Vec<Rule>>,
+    },
+}
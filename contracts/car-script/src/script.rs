@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_schema::cw_serde;
+use titans_car_sdk::{TurnInfo, BANANA};
+
+// Bounds on the stored rules, they are evaluated at every turn of the car
+pub const MAX_RULES: usize = 32;
+pub const MAX_CONDITIONS: usize = 8;
+pub const MAX_ORDERS: usize = 8;
+// The price of each unit is computed when an order is evaluated
+pub const MAX_ORDER_AMOUNT: u64 = 100;
+
+// `if <all conditions hold> then <buy the orders>`, a rule without condition always holds
+#[cw_serde]
+pub struct Rule {
+    pub when: Vec<Condition>,
+    pub then: Vec<Order>,
+}
+
+// `<subject> <op> <value>`, e.g. {"subject": "rank", "op": "ge", "value": 1}
+#[cw_serde]
+pub struct Condition {
+    pub subject: Subject,
+    pub op: Cmp,
+    pub value: u64,
+}
+
+#[cw_serde]
+pub enum Subject {
+    // 0 is the leader
+    Rank,
+    DistanceToLeader,
+    // Left before the target distance
+    DistanceToTarget,
+    Speed,
+    Balance,
+    Turn,
+    // Price of the next one, the condition fails when the action is not enabled
+    Price { action: String },
+}
+
+#[cw_serde]
+pub enum Cmp {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+}
+
+// A Buy message of the base contract
+#[cw_serde]
+pub struct Order {
+    pub action: String,
+    pub amount: u64,
+}
+
+impl Cmp {
+    pub fn holds(&self, lhs: u64, rhs: u64) -> bool {
+        match self {
+            Cmp::Lt => lhs < rhs,
+            Cmp::Le => lhs <= rhs,
+            Cmp::Eq => lhs == rhs,
+            Cmp::Ne => lhs != rhs,
+            Cmp::Ge => lhs >= rhs,
+            Cmp::Gt => lhs > rhs,
+        }
+    }
+}
+
+impl Subject {
+    pub fn value(&self, turn: &TurnInfo) -> Option<u64> {
+        let me = &turn.me;
+        match self {
+            Subject::Rank => Some(turn.rank() as u64),
+            Subject::DistanceToLeader => Some(turn.leader().y.saturating_sub(me.y)),
+            Subject::DistanceToTarget => Some(turn.target_distance.saturating_sub(me.y)),
+            Subject::Speed => Some(me.speed),
+            Subject::Balance => Some(me.balance),
+            Subject::Turn => Some(turn.turns),
+            Subject::Price { action } => turn.price(action),
+        }
+    }
+}
+
+impl Condition {
+    pub fn holds(&self, turn: &TurnInfo) -> bool {
+        self.subject
+            .value(turn)
+            .is_some_and(|value| self.op.holds(value, self.value))
+    }
+}
+
+// Why the rules can not be stored, None when they can
+pub fn validate(rules: &[Rule]) -> Option<String> {
+    if rules.len() > MAX_RULES {
+        return Some(format!("more than {MAX_RULES} rules"));
+    }
+    for (index, rule) in rules.iter().enumerate() {
+        if rule.when.len() > MAX_CONDITIONS {
+            return Some(format!(
+                "rule {index} has more than {MAX_CONDITIONS} conditions"
+            ));
+        }
+        if rule.then.is_empty() || rule.then.len() > MAX_ORDERS {
+            return Some(format!("rule {index} needs 1 to {MAX_ORDERS} orders"));
+        }
+        if rule.then.iter().any(|order| order.amount == 0) {
+            return Some(format!("rule {index} buys a zero amount"));
+        }
+        if rule
+            .then
+            .iter()
+            .any(|order| order.amount > MAX_ORDER_AMOUNT)
+        {
+            return Some(format!(
+                "rule {index} buys more than {MAX_ORDER_AMOUNT} at once"
+            ));
+        }
+        // The base contract drops one banana at a time
+        if rule
+            .then
+            .iter()
+            .any(|order| order.action == BANANA && order.amount > 1)
+        {
+            return Some(format!("rule {index} buys more than 1 banana at once"));
+        }
+    }
+    None
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Evaluation {
+    pub matched: Vec<u64>,
+    pub orders: Vec<Order>,
+    pub skipped: Vec<Order>,
+}
+
+// Every rule whose conditions hold adds its orders, in the order of the rules. An order
+// over the balance left by the previous ones, of an action not enabled, or over
+// MAX_ORDER_AMOUNT, is skipped. The units ordered earlier in the turn raise the price of the
+// next ones.
+pub fn evaluate(rules: &[Rule], turn: &TurnInfo) -> Evaluation {
+    let mut evaluation = Evaluation::default();
+    let mut balance = turn.me.balance;
+    let mut ordered: BTreeMap<&str, u64> = BTreeMap::new();
+    for (index, rule) in rules.iter().enumerate() {
+        if !rule.when.iter().all(|condition| condition.holds(turn)) {
+            continue;
+        }
+        evaluation.matched.push(index as u64);
+        for order in &rule.then {
+            let before = ordered
+                .get(order.action.as_str())
+                .copied()
+                .unwrap_or_default();
+            let Some(after) = before
+                .checked_add(order.amount)
+                .filter(|_| order.amount <= MAX_ORDER_AMOUNT)
+            else {
+                evaluation.skipped.push(order.clone());
+                continue;
+            };
+            let cost = turn
+                .cost(&order.action, after)
+                .zip(turn.cost(&order.action, before))
+                .map(|(total, before)| total - before)
+                .filter(|cost| *cost <= balance);
+            match cost {
+                Some(cost) => {
+                    balance -= cost;
+                    ordered.insert(&order.action, after);
+                    evaluation.orders.push(order.clone());
+                }
+                None => evaluation.skipped.push(order.clone()),
+            }
+        }
+    }
+    evaluation
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::from_slice;
+    use titans_car_sdk::{
+        testing::{car, TurnBuilder},
+        ACCELERATE, BANANA, SHELL, SHIELD,
+    };
+    use titans_engine::{Race, Rules};
+
+    use super::*;
+
+    // We are second behind "a", every action costs 1 except the shell at 10, the shield
+    // is not enabled
+    fn turn(balance: u64) -> TurnInfo {
+        let standings = vec![car("a", 160, 9), car("me", 100, 5), car("b", 90, 4)];
+        TurnBuilder::new("me", standings)
+            .turns(12)
+            .balance(balance)
+            .price(1)
+            .price_of(SHELL, 10)
+            .without(SHIELD)
+            .build()
+    }
+
+    fn when(subject: Subject, op: Cmp, value: u64) -> Condition {
+        Condition { subject, op, value }
+    }
+
+    fn order(action: &str, amount: u64) -> Order {
+        Order {
+            action: action.to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_conditions() {
+        let turn = turn(40);
+        let holds = |subject, op, value| when(subject, op, value).holds(&turn);
+        assert!(holds(Subject::Rank, Cmp::Eq, 1));
+        assert!(holds(Subject::DistanceToLeader, Cmp::Ge, 60));
+        assert!(!holds(Subject::DistanceToLeader, Cmp::Gt, 60));
+        assert!(holds(Subject::DistanceToTarget, Cmp::Eq, 900));
+        assert!(holds(Subject::Speed, Cmp::Lt, 6));
+        assert!(holds(Subject::Balance, Cmp::Le, 40));
+        assert!(holds(Subject::Turn, Cmp::Ne, 11));
+        let price = |action: &str| Subject::Price {
+            action: action.to_string(),
+        };
+        assert!(holds(price(SHELL), Cmp::Eq, 10));
+        // A price of an action not enabled never holds
+        assert!(!holds(price(SHIELD), Cmp::Ge, 0));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let rules = vec![
+            // Not the leader and a shell is cheap enough
+            Rule {
+                when: vec![
+                    when(Subject::Rank, Cmp::Ge, 1),
+                    when(
+                        Subject::Price {
+                            action: SHELL.to_string(),
+                        },
+                        Cmp::Le,
+                        20,
+                    ),
+                ],
+                then: vec![order(SHELL, 1)],
+            },
+            // Already the leader, does not hold
+            Rule {
+                when: vec![when(Subject::Rank, Cmp::Eq, 0)],
+                then: vec![order(ACCELERATE, 10)],
+            },
+            // Always
+            Rule {
+                when: vec![],
+                then: vec![order(SHIELD, 1), order(ACCELERATE, 5), order(ACCELERATE, 5)],
+            },
+        ];
+
+        // The shell takes 10 of the balance, 9 is left for one order of 5 accelerate,
+        // shield is not enabled
+        assert_eq!(
+            evaluate(&rules, &turn(19)),
+            Evaluation {
+                matched: vec![0, 2],
+                orders: vec![order(SHELL, 1), order(ACCELERATE, 5)],
+                skipped: vec![order(SHIELD, 1), order(ACCELERATE, 5)],
+            }
+        );
+        assert_eq!(evaluate(&[], &turn(20)), Evaluation::default());
+    }
+
+    #[test]
+    fn test_evaluate_rising_prices() {
+        // Accelerations at the first turn of a race cost 12, 14, 18 then 22
+        let race = Race::new(Rules::default(), &["me", "other"]);
+        let mut turn = TurnInfo::from_race(&race, "me").unwrap();
+        let rules = vec![Rule {
+            when: vec![],
+            then: vec![order(ACCELERATE, 2), order(ACCELERATE, 2)],
+        }];
+
+        turn.me.balance = 65;
+        assert_eq!(
+            evaluate(&rules, &turn),
+            Evaluation {
+                matched: vec![0],
+                orders: vec![order(ACCELERATE, 2)],
+                skipped: vec![order(ACCELERATE, 2)],
+            }
+        );
+        turn.me.balance = 66;
+        assert_eq!(evaluate(&rules, &turn).orders.len(), 2);
+    }
+
+    #[test]
+    fn test_validate() {
+        let rule = |then| Rule { when: vec![], then };
+        assert_eq!(validate(&[rule(vec![order(ACCELERATE, 1)])]), None);
+        assert!(validate(&[rule(vec![])]).is_some());
+        assert!(validate(&[rule(vec![order(ACCELERATE, 0)])]).is_some());
+        assert_eq!(
+            validate(&[rule(vec![order(ACCELERATE, MAX_ORDER_AMOUNT)])]),
+            None
+        );
+        assert!(validate(&[rule(vec![order(ACCELERATE, MAX_ORDER_AMOUNT + 1)])]).is_some());
+        assert!(validate(&[rule(vec![order(BANANA, 2)])]).is_some());
+        assert!(validate(&vec![rule(vec![order(ACCELERATE, 1)]); MAX_RULES + 1]).is_some());
+    }
+
+    #[test]
+    fn test_rules_json() {
+        let rules: Vec<Rule> = from_slice(
+            br#"[{
+                "when": [
+                    {"subject": "distance_to_leader", "op": "gt", "value": 50},
+                    {"subject": {"price": {"action": "shell"}}, "op": "le", "value": 20}
+                ],
+                "then": [{"action": "shell", "amount": 1}]
+            }]"#,
+        )
+        .unwrap();
+        assert_eq!(rules[0].when[0].subject, Subject::DistanceToLeader);
+        assert_eq!(rules[0].then, vec![order(SHELL, 1)]);
+    }
+}
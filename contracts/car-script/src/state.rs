@@ -0,0 +1,11 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+
+use crate::script::Rule;
+
+pub const BASE_CAR_ADDR: Item<Addr> = Item::new("base_car_addr");
+
+// ADMIN is the instantiator of the car, the only one allowed to replace the rules
+pub const ADMIN: Item<Addr> = Item::new("admin");
+
+pub const RULES: Item<Vec<Rule>> = Item::new("rules");
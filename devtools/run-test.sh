@@ -0,0 +1,6 @@
+#!/usr/bin/env bash
+# Race between the example cars on a mock chain, see `cargo race -- --help`
+set -e
+
+cd "$(dirname "$0")/../contracts/base-car"
+cargo race "$@"
@@ -0,0 +1,206 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    to_binary, Addr, CosmosMsg, MessageInfo, QuerierWrapper, StdError, StdResult, WasmMsg,
+};
+use titans_engine::{ACCELERATE, BANANA, SHELL, SHIELD, SUPER_SHELL};
+
+use crate::{
+    msg::{
+        ActionsResponse, ActionsSoldResponse, AllCarDataReponse, CarExecuteMsg, ExecuteMsg,
+        GameStateResponse, QueryMsg, StandingsResponse, TurnOrderResponse,
+    },
+    turn::TurnInfo,
+    types::CarData,
+};
+
+/// BaseCarClient is a wrapper around the address of the base contract with typed
+/// queries and builders of the action messages.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct BaseCarClient(pub Addr);
+
+impl BaseCarClient {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    // Only the base contract can make a car play, anyone else could make it spend its
+    // balance
+    pub fn assert_caller(&self, info: &MessageInfo) -> StdResult<()> {
+        if info.sender != self.0 {
+            return Err(StdError::generic_err(format!(
+                "Unauthorized: only {} can make the car play",
+                self.0
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn call<T: Into<ExecuteMsg>>(&self, msg: T) -> StdResult<CosmosMsg> {
+        let msg = to_binary(&msg.into())?;
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg,
+            funds: vec![],
+        }
+        .into())
+    }
+
+    // Buy `amount` of an action of the race config
+    pub fn buy(&self, action: &str, amount: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Buy {
+            action: action.to_string(),
+            amount,
+        })
+    }
+
+    pub fn accelerate(&self, amount: u64) -> StdResult<CosmosMsg> {
+        self.buy(ACCELERATE, amount)
+    }
+
+    pub fn shell(&self, amount: u64) -> StdResult<CosmosMsg> {
+        self.buy(SHELL, amount)
+    }
+
+    pub fn super_shell(&self, amount: u64) -> StdResult<CosmosMsg> {
+        self.buy(SUPER_SHELL, amount)
+    }
+
+    pub fn banana(&self) -> StdResult<CosmosMsg> {
+        self.buy(BANANA, 1)
+    }
+
+    pub fn shield(&self, amount: u64) -> StdResult<CosmosMsg> {
+        self.buy(SHIELD, amount)
+    }
+
+    pub fn game_state(&self, querier: &QuerierWrapper) -> StdResult<GameStateResponse> {
+        querier.query_wasm_smart(self.addr(), &QueryMsg::GetGameState)
+    }
+
+    pub fn all_car_data(&self, querier: &QuerierWrapper) -> StdResult<Vec<(Addr, CarData)>> {
+        let res: AllCarDataReponse =
+            querier.query_wasm_smart(self.addr(), &QueryMsg::GetAllCarData)?;
+        Ok(res.all_cars)
+    }
+
+    // Data of a car, None if it is not in the race
+    pub fn car_data(&self, querier: &QuerierWrapper, car: &Addr) -> StdResult<Option<CarData>> {
+        Ok(self
+            .all_car_data(querier)?
+            .into_iter()
+            .find(|(addr, _)| addr == car)
+            .map(|(_, car_data)| car_data))
+    }
+
+    // Cars from the first to the last one
+    pub fn standings(&self, querier: &QuerierWrapper) -> StdResult<Vec<CarData>> {
+        let res: StandingsResponse =
+            querier.query_wasm_smart(self.addr(), &QueryMsg::GetStandings)?;
+        Ok(res.cars)
+    }
+
+    // Actions of the race, priced for `car` when each car has its own supply
+    pub fn list_actions(
+        &self,
+        querier: &QuerierWrapper,
+        car: Option<&Addr>,
+    ) -> StdResult<ActionsResponse> {
+        querier.query_wasm_smart(self.addr(), &QueryMsg::ListActions { car: car.cloned() })
+    }
+
+    pub fn actions_sold(&self, querier: &QuerierWrapper) -> StdResult<ActionsSoldResponse> {
+        querier.query_wasm_smart(self.addr(), &QueryMsg::GetActionsSold)
+    }
+
+    // Everything a car needs to decide its turn, `car` is the address of the car
+    pub fn turn_info(&self, querier: &QuerierWrapper, car: &Addr) -> StdResult<TurnInfo> {
+        let game_state = self.game_state(querier)?;
+        let standings = self.standings(querier)?;
+        let me = standings
+            .iter()
+            .find(|car_data| car_data.addr == car)
+            .cloned()
+            .ok_or_else(|| StdError::not_found(format!("car {}", car)))?;
+
+        Ok(TurnInfo {
+            turns: game_state.turns,
+            me,
+            standings,
+            actions: self.list_actions(querier, Some(car))?.actions,
+            post_sell_speed: game_state.config.post_sell_speed,
+            target_distance: game_state.config.target_distance,
+        })
+    }
+
+    pub fn turn_order(&self, querier: &QuerierWrapper, round: u64) -> StdResult<TurnOrderResponse> {
+        querier.query_wasm_smart(self.addr(), &QueryMsg::GetTurnOrder { round })
+    }
+}
+
+/// CarClient is a wrapper around the address of a car contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct CarClient(pub Addr);
+
+impl CarClient {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    pub fn call<T: Into<CarExecuteMsg>>(&self, msg: T) -> StdResult<CosmosMsg> {
+        let msg = to_binary(&msg.into())?;
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{from_binary, testing::mock_info, Addr, CosmosMsg, WasmMsg};
+
+    use super::BaseCarClient;
+    use crate::msg::ExecuteMsg;
+
+    #[test]
+    fn test_assert_caller() {
+        let client = BaseCarClient(Addr::unchecked("base"));
+        client.assert_caller(&mock_info("base", &[])).unwrap();
+        assert!(client.assert_caller(&mock_info("owner", &[])).is_err());
+    }
+
+    #[test]
+    fn test_action_messages() {
+        let client = BaseCarClient(Addr::unchecked("base"));
+
+        let CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) = client.accelerate(2).unwrap()
+        else {
+            panic!("not a wasm execute message");
+        };
+        assert_eq!(contract_addr, "base");
+        assert_eq!(
+            from_binary::<ExecuteMsg>(&msg).unwrap(),
+            ExecuteMsg::Buy {
+                action: "accelerate".to_string(),
+                amount: 2
+            }
+        );
+
+        let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = client.banana().unwrap() else {
+            panic!("not a wasm execute message");
+        };
+        assert_eq!(
+            from_binary::<ExecuteMsg>(&msg).unwrap(),
+            ExecuteMsg::Buy {
+                action: "banana".to_string(),
+                amount: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,15 @@
+pub mod client;
+pub mod msg;
+pub mod params;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod turn;
+pub mod types;
+
+pub use crate::client::{BaseCarClient, CarClient};
+pub use crate::msg::{CarExecuteMsg, StrategyExecuteMsg, StrategyResponse};
+pub use crate::params::StrategyParams;
+pub use crate::turn::TurnInfo;
+
+// Names of the default actions
+pub use titans_engine::{ACCELERATE, BANANA, SHELL, SHIELD, SUPER_SHELL};
@@ -0,0 +1,233 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, Uint128};
+
+use titans_engine::{ActionEffect, ActionSupply, PricingSpec};
+
+use crate::turn::TurnInfo;
+use crate::types::{CarData, Config, RaceRecord, State};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Register { car_addrs: Vec<Addr> },
+
+    Reset {},
+
+    Play { turns_to_play: u64 },
+
+    // Anyone can advance an active race when `Config.crank_enabled` is set
+    Crank { turns: u64 },
+
+    // Fund the rewards paid to the crankers
+    FundCrankPool {},
+
+    // Buy `amount` of an action from `Config.actions`
+    Buy { action: String, amount: u64 },
+
+    // Shorthands of `Buy` for the actions of the default config
+    BuyShell { amount: u64 },
+
+    BuyAccelerate { amount: u64 },
+
+    BuyBanana {},
+
+    BuyShield { amount: u64 },
+
+    BuySuperShell { amount: u64 },
+
+    // Owner only: propose a new owner, who has to accept it
+    ProposeOwner { new_owner: String },
+
+    AcceptOwnership {},
+
+    // Owner only: operators can register, reset and play races
+    AddOperator { operator: String },
+
+    RemoveOperator { operator: String },
+
+    // Owner only: config of the next race, can not be changed while racing
+    UpdateConfig { config: Box<Config> },
+
+    // Owner only: save a named config, replacing the preset with the same name
+    SavePreset { name: String, config: Box<Config> },
+
+    DeletePreset { name: String },
+
+    // Owner and operators: the next race uses the config of a preset, can not be changed
+    // while racing
+    UsePreset { name: String },
+
+    // Owner only: block playing, registering and buying actions
+    Pause {},
+
+    Unpause {},
+
+    // Cancel the current race and refund the crank pool to its funders
+    Abort { reason: String },
+
+    // Owner and cars commit to the turn order seed with the hex encoded sha256 of a secret,
+    // before the first turn when `Config.shuffle_turn_order` is set
+    CommitSeed { commitment: String },
+
+    // Cars reveal their secret first, the seed is fixed when the owner reveals
+    RevealSeed { secret: String },
+}
+
+#[cw_serde]
+pub struct AllCarDataReponse {
+    pub all_cars: Vec<(Addr, CarData)>,
+}
+
+#[cw_serde]
+pub struct StandingsResponse {
+    pub cars: Vec<CarData>,
+}
+
+#[cw_serde]
+pub struct TurnOrderResponse {
+    pub round: u64,
+    pub order: Vec<Addr>,
+    pub seed: Option<String>,
+}
+
+#[cw_serde]
+pub struct ActionInfo {
+    pub name: String,
+    pub effect: ActionEffect,
+    pub pricing: PricingSpec,
+    // Sold amount the price follows: to all the cars, or to the car with the per car supply
+    pub sold: u64,
+    // Price of the next one at the current turn
+    pub price: u64,
+}
+
+#[cw_serde]
+pub struct ActionsResponse {
+    pub actions: Vec<ActionInfo>,
+}
+
+#[cw_serde]
+pub struct CarActionsSold {
+    pub car: Addr,
+    pub sold: Vec<(String, u64)>,
+}
+
+#[cw_serde]
+pub struct ActionsSoldResponse {
+    // The counters the prices follow in the current race
+    pub supply: ActionSupply,
+    pub global: Vec<(String, u64)>,
+    pub per_car: Vec<CarActionsSold>,
+}
+
+#[cw_serde]
+pub struct OwnerResponse {
+    pub owner: Option<String>,
+    pub pending_owner: Option<String>,
+}
+
+#[cw_serde]
+pub struct CrankPoolResponse {
+    pub pool: Uint128,
+    pub reward: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct PausedResponse {
+    pub paused: bool,
+}
+
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<String>,
+}
+
+#[cw_serde]
+pub struct GameStateResponse {
+    pub turns: u64,
+    pub config: Config,
+    // Preset the config comes from
+    pub preset: Option<String>,
+    pub state: State,
+}
+
+#[cw_serde]
+pub struct Preset {
+    pub name: String,
+    pub config: Config,
+}
+
+#[cw_serde]
+pub struct PresetsResponse {
+    pub presets: Vec<Preset>,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(OwnerResponse)]
+    GetOwner,
+
+    #[returns(OperatorsResponse)]
+    GetOperators,
+
+    #[returns(AllCarDataReponse)]
+    GetAllCarData,
+
+    #[returns(GameStateResponse)]
+    GetGameState,
+
+    // Cars sorted from the first to the last one
+    #[returns(StandingsResponse)]
+    GetStandings,
+
+    // Actions enabled in the current race. With the per car supply, the prices are the ones
+    // `car` pays, otherwise they follow the sales to all the cars
+    #[returns(ActionsResponse)]
+    ListActions { car: Option<Addr> },
+
+    // Sold amount of each action, to all the cars and to each car
+    #[returns(ActionsSoldResponse)]
+    GetActionsSold,
+
+    #[returns(TurnOrderResponse)]
+    GetTurnOrder { round: u64 },
+
+    #[returns(CrankPoolResponse)]
+    GetCrankPool,
+
+    #[returns(PausedResponse)]
+    IsPaused,
+
+    #[returns(RaceRecord)]
+    GetRaceHistory { game_id: u64 },
+
+    // Named configs saved by the owner, sorted by name
+    #[returns(PresetsResponse)]
+    ListPresets,
+}
+
+// Interface every car implements, the base contract sends `TakeTurn` to the car playing
+// the current turn
+#[cw_serde]
+pub enum CarExecuteMsg {
+    TakeTurn {},
+    Ping {},
+}
+
+// Interface of a strategy contract playing for a proxy car: the proxy sends its turn and buys
+// the orders of the `StrategyResponse` set as the data of the response
+#[cw_serde]
+pub enum StrategyExecuteMsg {
+    TakeTurn { turn: TurnInfo },
+}
+
+#[cw_serde]
+pub struct StrategyResponse {
+    // (action, amount), bought in order
+    pub orders: Vec<(String, u64)>,
+}
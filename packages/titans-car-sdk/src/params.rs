@@ -0,0 +1,249 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use titans_engine::{ACCELERATE, BANANA, SHELL, SHIELD, SUPER_SHELL};
+
+use crate::turn::TurnInfo;
+
+// A strategy whose decisions are data: the same parameters play the parameterized car
+// contract and the native simulator. Shares are in per mille of the balance, ranks start
+// at 0 for the leader, a zero distance or rank threshold disables its action.
+#[cw_serde]
+pub struct StrategyParams {
+    // Balance kept for the end game sprint
+    pub reserve_per_mille: u64,
+    // Spent per turn, out of the balance above the reserve
+    pub budget_per_mille: u64,
+
+    // Accelerate until this speed, by at most `max_accelerate` per turn
+    pub target_speed: u64,
+    pub max_accelerate: u64,
+
+    // Shell the car right ahead from this rank, when it is faster than us by at least
+    // `shell_speed_gap`
+    pub shell_min_rank: u64,
+    pub shell_speed_gap: u64,
+
+    // Super shell the cars ahead from this rank, when the leader is faster than us
+    pub super_shell_min_rank: u64,
+
+    // Drop a banana when a car is this close behind
+    pub banana_distance: u64,
+
+    // Keep a shield when a car is this close behind
+    pub shield_distance: u64,
+
+    // Spend the reserve and accelerate at full rate this close to the target distance
+    pub sprint_distance: u64,
+}
+
+impl Default for StrategyParams {
+    fn default() -> Self {
+        Self {
+            reserve_per_mille: 250,
+            budget_per_mille: 50,
+            target_speed: 15,
+            max_accelerate: 3,
+            shell_min_rank: 1,
+            shell_speed_gap: 3,
+            super_shell_min_rank: 2,
+            banana_distance: 0,
+            shield_distance: 30,
+            sprint_distance: 150,
+        }
+    }
+}
+
+// Instantiate message of the parameterized car
+#[cw_serde]
+pub struct ParamsCarInstantiateMsg {
+    pub base_car_addr: Addr,
+    pub params: StrategyParams,
+}
+
+impl StrategyParams {
+    // Actions to buy this turn, in order
+    pub fn decide(&self, turn: &TurnInfo) -> Vec<(String, u64)> {
+        let mut orders = vec![];
+        let me = &turn.me;
+        let rank = turn.rank() as u64;
+        let sprint = turn.target_distance.saturating_sub(me.y) <= self.sprint_distance;
+
+        let mut budget = if sprint {
+            me.balance
+        } else {
+            let available = me.balance - per_mille(me.balance, self.reserve_per_mille);
+            per_mille(available, self.budget_per_mille)
+        };
+        let mut buy = |orders: &mut Vec<(String, u64)>, action: &str| match turn
+            .price(action)
+            .filter(|price| *price <= budget)
+        {
+            Some(price) => {
+                orders.push((action.to_string(), 1));
+                budget -= price;
+                true
+            }
+            None => false,
+        };
+
+        let ahead = rank
+            .checked_sub(1)
+            .map(|rank| &turn.standings[rank as usize]);
+        let shell_ahead = ahead.is_some_and(|ahead| {
+            self.shell_min_rank > 0
+                && rank >= self.shell_min_rank
+                && ahead.shield == 0
+                && ahead.speed > turn.post_sell_speed
+                && ahead.speed >= me.speed.saturating_add(self.shell_speed_gap)
+        });
+        let leader = turn.leader();
+        let super_shell = self.super_shell_min_rank > 0
+            && rank >= self.super_shell_min_rank
+            && leader.speed > turn.post_sell_speed
+            && leader.speed > me.speed;
+        if !(shell_ahead && buy(&mut orders, SHELL)) && super_shell {
+            buy(&mut orders, SUPER_SHELL);
+        }
+
+        let within = |distance: u64| {
+            distance > 0
+                && turn
+                    .standings
+                    .iter()
+                    .any(|car| car.y < me.y && me.y - car.y <= distance)
+        };
+        if within(self.banana_distance) {
+            buy(&mut orders, BANANA);
+        }
+        if me.shield == 0 && within(self.shield_distance) {
+            buy(&mut orders, SHIELD);
+        }
+
+        let wanted = if sprint {
+            self.max_accelerate
+        } else {
+            self.target_speed
+                .saturating_sub(me.speed)
+                .min(self.max_accelerate)
+        };
+        let amount = turn.affordable(ACCELERATE, budget, wanted);
+        if amount > 0 {
+            orders.push((ACCELERATE.to_string(), amount));
+        }
+
+        orders
+    }
+}
+
+// Share of an amount, in u128 so that no balance overflows. At most 1000 per mille, the
+// share fits back in a u64.
+fn per_mille(amount: u64, share: u64) -> u64 {
+    Uint128::from(amount)
+        .multiply_ratio(share.min(1000), 1000u64)
+        .u128() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StrategyParams;
+    use crate::{
+        testing::{car, order, TurnBuilder},
+        ACCELERATE, BANANA, SHELL, SHIELD, SUPER_SHELL,
+    };
+
+    #[test]
+    fn test_accelerate_to_target_speed() {
+        let params = StrategyParams {
+            shield_distance: 0,
+            ..StrategyParams::default()
+        };
+        let turn = TurnBuilder::new("me", vec![car("me", 50, 13), car("other", 40, 2)])
+            .price(5)
+            .build();
+        assert_eq!(params.decide(&turn), vec![order(ACCELERATE, 2)]);
+
+        // The budget is a share of the balance above the reserve: 1000 * 3/4 / 20 = 37
+        let params = StrategyParams {
+            target_speed: 100,
+            max_accelerate: 10,
+            ..params
+        };
+        assert_eq!(params.decide(&turn), vec![order(ACCELERATE, 7)]);
+    }
+
+    #[test]
+    fn test_large_balance() {
+        let params = StrategyParams {
+            target_speed: 100,
+            shield_distance: 0,
+            ..StrategyParams::default()
+        };
+        // The budget is a share of the balance above the reserve: 3/4 / 20
+        let balance = u64::MAX;
+        let budget = (balance - balance / 4) / 20;
+        let turn = TurnBuilder::new("me", vec![car("me", 50, 13), car("other", 40, 2)])
+            .balance(balance)
+            .price(budget / 2)
+            .build();
+        assert_eq!(params.decide(&turn), vec![order(ACCELERATE, 2)]);
+    }
+
+    #[test]
+    fn test_shell_triggers() {
+        let params = StrategyParams::default();
+
+        // The car ahead is not faster enough for a shell
+        let standings = vec![car("leader", 60, 6), car("me", 50, 5), car("last", 0, 0)];
+        assert!(!params
+            .decide(&TurnBuilder::new("me", standings).price(5).build())
+            .contains(&order(SHELL, 1)));
+
+        let standings = vec![car("leader", 60, 8), car("me", 50, 5), car("last", 0, 0)];
+        assert_eq!(
+            params.decide(&TurnBuilder::new("me", standings).price(5).build())[0],
+            order(SHELL, 1)
+        );
+
+        // A super shell from the third place, when the car ahead can not be shelled
+        let mut second = car("second", 55, 8);
+        second.shield = 1;
+        let standings = vec![car("leader", 60, 8), second, car("me", 50, 5)];
+        assert_eq!(
+            params.decide(&TurnBuilder::new("me", standings).price(5).build())[0],
+            order(SUPER_SHELL, 1)
+        );
+
+        // Disabled by a zero rank
+        let params = StrategyParams {
+            shell_min_rank: 0,
+            super_shell_min_rank: 0,
+            ..params
+        };
+        let standings = vec![car("leader", 60, 8), car("me", 50, 5)];
+        assert!(!params
+            .decide(&TurnBuilder::new("me", standings).price(5).build())
+            .iter()
+            .any(|(action, _)| action == SHELL || action == SUPER_SHELL));
+    }
+
+    #[test]
+    fn test_defense_and_sprint() {
+        let params = StrategyParams {
+            banana_distance: 10,
+            ..StrategyParams::default()
+        };
+        let standings = vec![car("me", 950, 20), car("other", 945, 5)];
+        let orders = params.decide(&TurnBuilder::new("me", standings).price(5).build());
+        assert_eq!(
+            orders,
+            vec![order(BANANA, 1), order(SHIELD, 1), order(ACCELERATE, 3)]
+        );
+
+        // Nobody close behind, the sprint spends the reserve but not more than the max rate
+        let standings = vec![car("me", 950, 20), car("other", 500, 5)];
+        assert_eq!(
+            params.decide(&TurnBuilder::new("me", standings).price(5).build()),
+            vec![order(ACCELERATE, 3)]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use cosmwasm_std::{
+    from_slice, testing::MockQuerier, to_binary, Addr, ContractResult, SystemResult, WasmQuery,
+};
+use titans_engine::{pricing::WAD, PricingSpec};
+
+use crate::{
+    msg::{ActionInfo, AllCarDataReponse, GameStateResponse, QueryMsg},
+    types::{CarData, Config, State},
+    TurnInfo,
+};
+
+// A car with a balance of 1000 and no shield
+pub fn car(name: &str, y: u64, speed: u64) -> CarData {
+    CarData {
+        balance: 1000,
+        addr: Addr::unchecked(name),
+        y,
+        speed,
+        shield: 0,
+    }
+}
+
+// An order as returned by the strategies
+pub fn order(action: &str, amount: u64) -> (String, u64) {
+    (action.to_string(), amount)
+}
+
+// Builds the TurnInfo of a car at the turn 10 of a race with the default config, where
+// every unit of the actions costs 10
+pub struct TurnBuilder {
+    turn: TurnInfo,
+}
+
+impl TurnBuilder {
+    // `me` is one of the cars, sorted from the first to the last one
+    pub fn new(me: &str, standings: Vec<CarData>) -> Self {
+        let config = Config::default();
+        Self {
+            turn: TurnInfo {
+                turns: 10,
+                me: standings
+                    .iter()
+                    .find(|car| car.addr == me)
+                    .expect("me is not in the standings")
+                    .clone(),
+                standings,
+                actions: config
+                    .actions
+                    .iter()
+                    .map(|action| ActionInfo {
+                        name: action.name.clone(),
+                        effect: action.effect,
+                        pricing: flat_pricing(10),
+                        sold: 0,
+                        price: 10,
+                    })
+                    .collect(),
+                post_sell_speed: config.post_sell_speed,
+                target_distance: config.target_distance,
+            },
+        }
+    }
+
+    pub fn turns(mut self, turns: u64) -> Self {
+        self.turn.turns = turns;
+        self
+    }
+
+    // Balance of `me`, in the standings too
+    pub fn balance(mut self, balance: u64) -> Self {
+        self.turn.me.balance = balance;
+        for car in &mut self.turn.standings {
+            if car.addr == self.turn.me.addr {
+                car.balance = balance;
+            }
+        }
+        self
+    }
+
+    // Price of every unit of every action
+    pub fn price(mut self, price: u64) -> Self {
+        for action in &mut self.turn.actions {
+            action.pricing = flat_pricing(price);
+            action.price = price;
+        }
+        self
+    }
+
+    pub fn price_of(mut self, name: &str, price: u64) -> Self {
+        for action in &mut self.turn.actions {
+            if action.name == name {
+                action.pricing = flat_pricing(price);
+                action.price = price;
+            }
+        }
+        self
+    }
+
+    // The action is not enabled in the race
+    pub fn without(mut self, name: &str) -> Self {
+        self.turn.actions.retain(|action| action.name != name);
+        self
+    }
+
+    pub fn build(self) -> TurnInfo {
+        self.turn
+    }
+}
+
+// The base contract answers with the state of the race, its turns counter and the registered
+// cars, each with a balance of 100
+pub fn mock_base_car(querier: &mut MockQuerier, state: State, turns: u64, cars: Vec<Addr>) {
+    querier.update_wasm(move |query| {
+        let WasmQuery::Smart { msg, .. } = query else {
+            panic!("unexpected query");
+        };
+        let res = match from_slice(msg).unwrap() {
+            QueryMsg::GetGameState => to_binary(&GameStateResponse {
+                turns,
+                config: Config::default(),
+                preset: None,
+                state: state.clone(),
+            }),
+            QueryMsg::GetAllCarData => to_binary(&AllCarDataReponse {
+                all_cars: cars
+                    .iter()
+                    .map(|car| (car.clone(), CarData::at_start(car.clone(), 100)))
+                    .collect(),
+            }),
+            _ => panic!("unexpected query"),
+        };
+        SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+    });
+}
+
+// The same price for every unit, whatever the turn and the sales
+fn flat_pricing(price: u64) -> PricingSpec {
+    PricingSpec {
+        target_price: price,
+        per_turn_decrease: 0,
+        sell_per_turn: WAD,
+    }
+}
@@ -0,0 +1,139 @@
+use cosmwasm_schema::cw_serde;
+use titans_engine::{pricing::get_action_cost, Race};
+
+use crate::{msg::ActionInfo, types::CarData};
+
+// What a car can see of the race at the start of its turn
+#[cw_serde]
+pub struct TurnInfo {
+    pub turns: u64,
+    pub me: CarData,
+    // Cars from the first to the last one
+    pub standings: Vec<CarData>,
+    // Actions enabled in the race with their next price
+    pub actions: Vec<ActionInfo>,
+    pub post_sell_speed: u64,
+    pub target_distance: u64,
+}
+
+impl TurnInfo {
+    // The turn of a car in a race played natively, prices follow the sales the car pays for
+    pub fn from_race(race: &Race, car: &str) -> Option<Self> {
+        let me = CarData::from_car(race.car(car)?);
+        let actions = race
+            .rules
+            .actions
+            .iter()
+            .map(|action| ActionInfo {
+                name: action.name.clone(),
+                effect: action.effect,
+                pricing: action.pricing.clone(),
+                sold: race.sold_for(car, &action.name),
+                price: race.action_cost(car, &action.name, 1).unwrap_or_default(),
+            })
+            .collect();
+
+        Some(Self {
+            turns: race.turns,
+            me,
+            standings: race
+                .standings()
+                .into_iter()
+                .map(CarData::from_car)
+                .collect(),
+            actions,
+            post_sell_speed: race.rules.post_sell_speed,
+            target_distance: race.rules.target_distance,
+        })
+    }
+
+    // Rank of the car, 0 is the leader
+    pub fn rank(&self) -> usize {
+        self.standings
+            .iter()
+            .position(|car| car.addr == self.me.addr)
+            .unwrap_or_default()
+    }
+
+    pub fn leader(&self) -> &CarData {
+        &self.standings[0]
+    }
+
+    // The car just behind us, if any
+    pub fn next_behind(&self) -> Option<&CarData> {
+        self.standings.get(self.rank() + 1)
+    }
+
+    fn action(&self, action: &str) -> Option<&ActionInfo> {
+        self.actions.iter().find(|info| info.name == action)
+    }
+
+    // Price of the next one, None when the action is not enabled in the race
+    pub fn price(&self, action: &str) -> Option<u64> {
+        self.action(action).map(|info| info.price)
+    }
+
+    // Cost of buying `amount` of an action this turn, each unit raises the price of the
+    // next one. A cost above our balance is only known to be above it. None when the action
+    // is not enabled in the race.
+    pub fn cost(&self, action: &str, amount: u64) -> Option<u64> {
+        self.action(action).map(|info| {
+            get_action_cost(
+                &info.pricing,
+                self.turns,
+                amount,
+                info.sold,
+                self.me.balance,
+            )
+        })
+    }
+
+    // Largest amount of an action, up to `max`, that `budget` pays this turn
+    pub fn affordable(&self, action: &str, budget: u64, max: u64) -> u64 {
+        let Some(info) = self.action(action) else {
+            return 0;
+        };
+        if info.pricing.target_price == 0 {
+            return max;
+        }
+
+        let mut cost: u64 = 0;
+        for amount in 0..max {
+            let price = get_action_cost(&info.pricing, self.turns, 1, info.sold + amount, u64::MAX);
+            cost = cost.saturating_add(price);
+            if cost > budget {
+                return amount;
+            }
+        }
+        max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use titans_engine::{Race, Rules, ACCELERATE, SHELL};
+
+    use super::TurnInfo;
+
+    #[test]
+    fn test_cost() {
+        let race = Race::new(Rules::default(), &["me", "other"]);
+        let mut turn = TurnInfo::from_race(&race, "me").unwrap();
+
+        // Accelerations at the first turn cost 12, 14 then 18
+        assert_eq!(turn.price(ACCELERATE), Some(12));
+        assert_eq!(turn.cost(ACCELERATE, 3), Some(44));
+        assert_eq!(turn.affordable(ACCELERATE, 43, 5), 2);
+        assert_eq!(turn.affordable(ACCELERATE, 44, 5), 3);
+        assert_eq!(turn.affordable(ACCELERATE, 1000, 5), 5);
+
+        // A second shell costs far more than the first one
+        assert_eq!(turn.affordable(SHELL, 15_000, 5), 2);
+        assert_eq!(turn.cost("nitro", 1), None);
+        assert_eq!(turn.affordable("nitro", 1000, 5), 0);
+
+        // Past our balance, the cost stops at the first unit above it
+        turn.me.balance = 40;
+        assert_eq!(turn.cost(ACCELERATE, u64::MAX), Some(44));
+    }
+}
@@ -0,0 +1,268 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin};
+use titans_engine::{default_actions, shuffle_seats, ActionConfig, ActionSupply, Car, Rules};
+
+#[cw_serde]
+pub struct Config {
+    // Number players required in each round
+    pub num_players: u64,
+
+    pub post_sell_speed: u64,
+
+    // Initial balance for each car
+    pub init_balance: u64,
+
+    // Target distance
+    pub target_distance: u64,
+
+    pub banana_speed_modifier: u64,
+
+    // Actions the cars can buy in the race, by their unique name
+    pub actions: Vec<ActionConfig>,
+
+    // Whether the prices follow the sales of all the cars or of each car
+    pub action_supply: ActionSupply,
+
+    // Permissionless crank config: anyone can play up to `crank_max_turns` turns,
+    // once every `crank_min_blocks` blocks, and gets `crank_reward` per turn played
+    pub crank_enabled: bool,
+    pub crank_max_turns: u64,
+    pub crank_min_blocks: u64,
+    pub crank_reward: Option<Coin>,
+
+    // Shuffle the turn order with a commit-reveal seed from the owner and the cars,
+    // once at the start of the race or again for each round
+    pub shuffle_turn_order: bool,
+    pub reshuffle_each_round: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            num_players: 3,
+            post_sell_speed: 1,
+            init_balance: 17500,
+            target_distance: 1000,
+            banana_speed_modifier: 0,
+            actions: default_actions(),
+            action_supply: ActionSupply::Global,
+            crank_enabled: false,
+            crank_max_turns: 10,
+            crank_min_blocks: 1,
+            crank_reward: None,
+            shuffle_turn_order: false,
+            reshuffle_each_round: false,
+        }
+    }
+}
+
+#[cw_serde]
+pub enum State {
+    Waiting,
+    Active,
+    Done,
+    Cancelled { reason: String },
+}
+
+impl Config {
+    pub fn action(&self, name: &str) -> Option<&ActionConfig> {
+        self.actions.iter().find(|action| action.name == name)
+    }
+
+    // Rules of the race played by the engine
+    pub fn rules(&self) -> Rules {
+        Rules {
+            post_sell_speed: self.post_sell_speed,
+            init_balance: self.init_balance,
+            target_distance: self.target_distance,
+            banana_speed_modifier: self.banana_speed_modifier,
+            actions: self.actions.clone(),
+            action_supply: self.action_supply,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct CarData {
+    pub balance: u64,
+    pub addr: Addr,
+    pub y: u64,
+    pub speed: u64,
+    pub shield: u64,
+}
+
+impl CarData {
+    #[allow(dead_code)]
+    fn test_car() -> Self {
+        Self {
+            balance: 1000,
+            addr: Addr::unchecked("input"),
+            y: 10,
+            speed: 2,
+            shield: 0,
+        }
+    }
+}
+
+impl CarData {
+    pub fn at_start(addr: Addr, balance: u64) -> Self {
+        Self {
+            balance,
+            addr,
+            y: 0,
+            speed: 0,
+            shield: 0,
+        }
+    }
+
+    pub fn from_car(car: &Car) -> Self {
+        Self {
+            balance: car.balance,
+            addr: Addr::unchecked(&car.id),
+            y: car.y,
+            speed: car.speed,
+            shield: car.shield,
+        }
+    }
+
+    pub fn to_car(&self) -> Car {
+        Car {
+            id: self.addr.to_string(),
+            balance: self.balance,
+            y: self.y,
+            speed: self.speed,
+            shield: self.shield,
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            balance: 0,
+            addr: Addr::unchecked(""),
+            y: 0,
+            speed: 0,
+            shield: 0,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct GameState {
+    // Increased by one for each new race
+    pub game_id: u64,
+
+    pub all_cars: Vec<Addr>,
+    pub turns: u64,
+
+    // The current state of the game: pre-start, started, done.
+    pub state: State,
+
+    // Game config
+    pub config: Config,
+
+    // Name of the preset the config was loaded from, None once the config is updated
+    pub preset: Option<String>,
+
+    // The banana in play, tracked by their y position.
+    pub bananas: Vec<u64>,
+
+    // Block height of the last permissionless crank
+    pub last_crank_height: u64,
+
+    // Hex encoded seed of the turn order, derived from the revealed secrets
+    pub seed: Option<String>,
+
+    // The revealed secrets in the order they are hashed into the seed, kept so the turn
+    // order can be verified
+    pub seed_reveals: Vec<SeedReveal>,
+}
+
+#[cw_serde]
+pub struct SeedReveal {
+    pub addr: Addr,
+    pub secret: String,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self {
+            game_id: 0,
+            all_cars: Vec::new(),
+            turns: 0,
+            state: State::Waiting,
+            config: Config::default(),
+            preset: None,
+            bananas: Vec::new(),
+            last_crank_height: 0,
+            seed: None,
+            seed_reveals: Vec::new(),
+        }
+    }
+}
+
+impl GameState {
+    pub fn for_test() -> Self {
+        let addr1 = Addr::unchecked("addr1");
+        let addr2 = Addr::unchecked("addr2");
+        let addr3 = Addr::unchecked("addr3");
+        let all_cars = vec![addr1.clone(), addr2.clone(), addr3.clone()];
+
+        Self {
+            game_id: 0,
+            all_cars,
+            turns: 3,
+            state: State::Active,
+            config: Config::default(),
+            preset: None,
+            bananas: vec![20, 10, 35, 30],
+            last_crank_height: 0,
+            seed: None,
+            seed_reveals: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, car_addrs: Vec<Addr>) {
+        self.all_cars = car_addrs;
+    }
+
+    pub fn total_cars(&self) -> u64 {
+        self.all_cars.len() as u64
+    }
+
+    // The car playing the current turn
+    pub fn current_turn_car(&self) -> Addr {
+        let round = self.turns / self.total_cars();
+        self.turn_order(round)[(self.turns % self.total_cars()) as usize].clone()
+    }
+
+    // The order cars take their turns in a round. Seats take turns in registration
+    // order, unless the turn order is shuffled with the seed of the race.
+    pub fn turn_order(&self, round: u64) -> Vec<Addr> {
+        let seed = match (&self.seed, self.config.shuffle_turn_order) {
+            (Some(seed), true) => seed,
+            _ => return self.all_cars.clone(),
+        };
+
+        // The order is shuffled once at the start, or again for each round
+        let round = if self.config.reshuffle_each_round {
+            round
+        } else {
+            0
+        };
+
+        shuffle_seats(seed, round, self.all_cars.len())
+            .into_iter()
+            .map(|seat| self.all_cars[seat].clone())
+            .collect()
+    }
+
+    pub fn can_play(&self) -> bool {
+        self.all_cars.len() as u64 == self.config.num_players
+    }
+}
+
+#[cw_serde]
+pub struct RaceRecord {
+    pub game: GameState,
+    pub cars: Vec<CarData>,
+}
@@ -0,0 +1,103 @@
+use cosmwasm_schema::cw_serde;
+
+use crate::pricing::WAD;
+
+// Names of the default actions
+pub const ACCELERATE: &str = "accelerate";
+pub const SHELL: &str = "shell";
+pub const SUPER_SHELL: &str = "super_shell";
+pub const BANANA: &str = "banana";
+pub const SHIELD: &str = "shield";
+
+#[cw_serde]
+pub struct ActionConfig {
+    pub name: String,
+    pub pricing: PricingSpec,
+    pub effect: ActionEffect,
+}
+
+impl ActionConfig {
+    pub fn new(name: &str, effect: ActionEffect) -> Self {
+        Self {
+            name: name.to_string(),
+            pricing: PricingSpec::default(),
+            effect,
+        }
+    }
+
+    pub fn with_pricing(
+        mut self,
+        target_price: u64,
+        per_turn_decrease: u64,
+        sell_per_turn: u64,
+    ) -> Self {
+        self.pricing = PricingSpec {
+            target_price,
+            per_turn_decrease,
+            sell_per_turn,
+        };
+        self
+    }
+}
+
+// The five actions of 0xTitans, with their pricing
+pub fn default_actions() -> Vec<ActionConfig> {
+    let decrease = 330_000_000_000_000_000;
+    let sell = 200_000_000_000_000_000;
+    vec![
+        ActionConfig::new(ACCELERATE, ActionEffect::Accelerate).with_pricing(10, decrease, 2 * WAD),
+        ActionConfig::new(SHELL, ActionEffect::Shell).with_pricing(200, decrease, sell),
+        ActionConfig::new(SUPER_SHELL, ActionEffect::SuperShell).with_pricing(
+            300,
+            350_000_000_000_000_000,
+            sell,
+        ),
+        ActionConfig::new(BANANA, ActionEffect::Banana).with_pricing(200, decrease, sell),
+        ActionConfig::new(SHIELD, ActionEffect::Shield).with_pricing(150, decrease, sell),
+    ]
+}
+
+// Price curve of an action, see `compute_action_price`. A zero target price makes the
+// action free.
+#[cw_serde]
+#[derive(Default)]
+pub struct PricingSpec {
+    pub target_price: u64,
+    // In WAD, 330_000_000_000_000_000 is a decrease of 33% per turn without sales
+    pub per_turn_decrease: u64,
+    // In WAD, the sales expected per turn
+    pub sell_per_turn: u64,
+}
+
+impl PricingSpec {
+    // The curve is defined for a decrease below 100% and some sales per turn
+    pub fn is_valid(&self) -> bool {
+        self.target_price == 0 || (self.per_turn_decrease < WAD && self.sell_per_turn > 0)
+    }
+}
+
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum ActionSupply {
+    // Every purchase raises the price for everyone, as in 0xMonaco
+    Global,
+    // Each car has its own price curve, only raised by its own purchases
+    PerCar,
+}
+
+// What happens when an action is bought, several actions can share the same effect
+// with different prices
+#[cw_serde]
+#[derive(Copy, Eq, Hash)]
+pub enum ActionEffect {
+    // Increase the speed of the buyer by the amount bought
+    Accelerate,
+    // Slow down the closest car ahead, unless it is shielded or a banana is hit first
+    Shell,
+    // Slow down every car ahead, shields do not protect
+    SuperShell,
+    // Drop a banana at the position of the buyer, one at a time
+    Banana,
+    // Protect the buyer from shells
+    Shield,
+}
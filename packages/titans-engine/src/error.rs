@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum EngineError {
+    #[error("Zero amount")]
+    ZeroAmount,
+
+    #[error("Unknown car {car}")]
+    UnknownCar { car: String },
+
+    #[error("Unknown action {action}")]
+    UnknownAction { action: String },
+
+    #[error("Can not buy {amount} {action} at once")]
+    InvalidActionAmount { action: String, amount: u64 },
+
+    #[error("Insufficient balance: {balance} < {cost}")]
+    InsufficientBalance { balance: u64, cost: u64 },
+
+    #[error("{action} overflows the car {car}")]
+    Overflow { car: String, action: String },
+}
@@ -0,0 +1,16 @@
+pub mod action;
+mod error;
+pub mod pricing;
+pub mod race;
+mod seats;
+
+#[cfg(test)]
+mod proptests;
+
+pub use crate::action::{
+    default_actions, ActionConfig, ActionEffect, ActionSupply, PricingSpec, ACCELERATE, BANANA,
+    SHELL, SHIELD, SUPER_SHELL,
+};
+pub use crate::error::EngineError;
+pub use crate::race::{ActionOutcome, Car, Race, Rules};
+pub use crate::seats::shuffle_seats;
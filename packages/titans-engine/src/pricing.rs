@@ -0,0 +1,223 @@
+use crate::action::PricingSpec;
+
+// Fixed point numbers with 18 decimals, 1.0 is WAD
+pub const WAD: u64 = 1_000_000_000_000_000_000;
+
+const IWAD: i128 = WAD as i128;
+// ln(2) with 36 decimals
+const LN_2_36: i128 = 693_147_180_559_945_309_417_232_121_458_176_568;
+// ln(2) with 18 decimals
+const LN_2: i128 = LN_2_36 / IWAD;
+
+// Cost of buying `amount` of an action after `sold` of it have been sold. The units are
+// priced one by one, the sum stops at the first unit past `max_cost`: a cost above
+// `max_cost` is only known to be above it.
+pub fn get_action_cost(
+    pricing: &PricingSpec,
+    turns: u64,
+    amount: u64,
+    sold: u64,
+    max_cost: u64,
+) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..amount {
+        sum = sum.saturating_add(compute_action_price(
+            pricing.target_price,
+            pricing.per_turn_decrease,
+            turns,
+            sold.saturating_add(i),
+            pricing.sell_per_turn,
+        ));
+        if sum > max_cost {
+            break;
+        }
+    }
+    sum
+}
+
+// Price of the next unit on the VRGDA curve of 0xMonaco:
+//
+//   target_price * (1 - per_turn_decrease) ^ (turns - (sold + 1) / sell_per_turn)
+//
+// `per_turn_decrease` and `sell_per_turn` are in WAD, the price is rounded down. It is at the
+// target price when the sales are on schedule, it decays while nothing is sold and grows
+// when the sales are ahead. A price too large to be paid is u64::MAX.
+pub fn compute_action_price(
+    target_price: u64,
+    per_turn_price_decrease: u64,
+    turn_since_start: u64,
+    sold: u64,
+    sell_per_turn_wad: u64,
+) -> u64 {
+    // A free action, the curve does not matter
+    if target_price == 0 {
+        return 0;
+    }
+    if per_turn_price_decrease >= WAD || sell_per_turn_wad == 0 {
+        return u64::MAX;
+    }
+
+    // Turns ahead (< 0) or behind (> 0) the schedule of the sales, in WAD
+    let sold_turns = wad_div(sold as i128 + 1, sell_per_turn_wad as i128);
+    let turns = turn_since_start as i128 * IWAD;
+    let Some(schedule) = sold_turns.and_then(|sold_turns| turns.checked_sub(sold_turns)) else {
+        return u64::MAX;
+    };
+
+    // ln(1 - d) <= 0, an overflow is far out of the range of `wad_exp`
+    let ln_decay = wad_ln(IWAD - per_turn_price_decrease as i128);
+    let exponent = match ln_decay.checked_mul(schedule) {
+        Some(product) => product / IWAD,
+        None if schedule > 0 => return 0,
+        None => return u64::MAX,
+    };
+
+    wad_exp(exponent)
+        .and_then(|exp| exp.checked_mul(target_price as i128))
+        .map(|price| price / IWAD)
+        .and_then(|price| u64::try_from(price).ok())
+        .unwrap_or(u64::MAX)
+}
+
+// `units / divisor_wad` in WAD, None on overflow
+fn wad_div(units: i128, divisor_wad: i128) -> Option<i128> {
+    // units * WAD * WAD / divisor, split to keep the intermediate values in range
+    let scale = IWAD * IWAD;
+    let quotient = units.checked_mul(scale / divisor_wad)?;
+    let remainder = units.checked_mul(scale % divisor_wad)? / divisor_wad;
+    quotient.checked_add(remainder)
+}
+
+// Natural logarithm of `x` > 0 in WAD
+fn wad_ln(x: i128) -> i128 {
+    // x = y * 2^k with y in [1, 2)
+    let mut k: i128 = 0;
+    let mut y = x;
+    while y >= 2 * IWAD {
+        y >>= 1;
+        k += 1;
+    }
+    while y < IWAD {
+        y <<= 1;
+        k -= 1;
+    }
+
+    // ln(y) = 2 * atanh(z) = 2 * (z + z^3 / 3 + z^5 / 5 + ...), z = (y - 1) / (y + 1) <= 1/3
+    let z = (y - IWAD) * IWAD / (y + IWAD);
+    let z2 = z * z / IWAD;
+    let mut term = z;
+    let mut sum = 0;
+    let mut n = 1;
+    while term != 0 {
+        sum += term / n;
+        term = term * z2 / IWAD;
+        n += 2;
+    }
+
+    k * LN_2_36 / IWAD + 2 * sum
+}
+
+// e^x in WAD, 0 when it rounds to 0, None when it does not fit
+fn wad_exp(x: i128) -> Option<i128> {
+    // e^-42 is below 1 / WAD
+    if x <= -42 * IWAD {
+        return Some(0);
+    }
+    // e^x * WAD does not fit an i128 past ~46
+    if x >= 46 * IWAD {
+        return None;
+    }
+
+    // x = k * ln(2) + r, with |r| <= ln(2) / 2
+    let k = (x * 2 + x.signum() * LN_2) / (2 * LN_2);
+    let r = (x * IWAD - k * LN_2_36) / IWAD;
+
+    // e^r with its Taylor series
+    let mut term = IWAD;
+    let mut sum = 0;
+    let mut n = 1;
+    while term != 0 {
+        sum += term;
+        term = term * r / IWAD / n;
+        n += 1;
+    }
+
+    Some(if k >= 0 { sum << k } else { sum >> -k })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_action_price, get_action_cost, WAD};
+    use crate::action::{default_actions, PricingSpec, ACCELERATE, SHELL, SHIELD, SUPER_SHELL};
+
+    fn pricing(action: &str) -> PricingSpec {
+        default_actions()
+            .into_iter()
+            .find(|a| a.name == action)
+            .unwrap()
+            .pricing
+    }
+
+    fn price(action: &str, turns: u64, sold: u64) -> u64 {
+        get_action_cost(&pricing(action), turns, 1, sold, u64::MAX)
+    }
+
+    // Expected prices are target * (1 - d) ^ (turns - (sold + 1) / sell_per_turn) computed
+    // with 60 digits, rounded down
+    #[test]
+    fn test_prices() {
+        // On schedule, 2 accelerate sold per turn, the price is the target
+        assert_eq!(price(ACCELERATE, 1, 1), 10);
+        assert_eq!(price(ACCELERATE, 5, 9), 10);
+        // Ahead of the schedule: 10 * 0.67 ^ -0.5
+        assert_eq!(price(ACCELERATE, 0, 0), 12);
+        assert_eq!(price(ACCELERATE, 0, 10), 90);
+        // Behind: 10 * 0.67 ^ 9.5
+        assert_eq!(price(ACCELERATE, 10, 0), 0);
+
+        assert_eq!(price(SHELL, 0, 0), 1481);
+        assert_eq!(price(SHELL, 10, 0), 27);
+        assert_eq!(price(SHELL, 30, 3), 3);
+        assert_eq!(price(SHELL, 2, 3), 270198);
+        assert_eq!(price(SUPER_SHELL, 0, 0), 2585);
+        assert_eq!(price(SUPER_SHELL, 10, 0), 34);
+        assert_eq!(price(SHIELD, 0, 0), 1111);
+        assert_eq!(price(SHIELD, 10, 0), 20);
+    }
+
+    #[test]
+    fn test_action_cost() {
+        // Each unit raises the price of the next one: 12 + 14 + 18 + 22 + 27
+        let pricing = pricing(ACCELERATE);
+        assert_eq!(get_action_cost(&pricing, 0, 5, 0, u64::MAX), 93);
+        assert_eq!(get_action_cost(&pricing, 0, 2, 3, u64::MAX), 22 + 27);
+        assert_eq!(get_action_cost(&pricing, 0, 0, 3, u64::MAX), 0);
+        assert_eq!(get_action_cost(&pricing, 0, 5, 0, 93), 93);
+        // Stops at the unit past the cap, whatever the amount
+        assert_eq!(get_action_cost(&pricing, 0, 5, 0, 40), 44);
+        assert_eq!(get_action_cost(&pricing, 0, u64::MAX, 0, 40), 44);
+    }
+
+    #[test]
+    fn test_price_bounds() {
+        let decrease = 330_000_000_000_000_000;
+        // A free action
+        assert_eq!(compute_action_price(0, decrease, 0, 100, 0), 0);
+        // No curve without a decrease below 1 and a sell rate
+        assert_eq!(compute_action_price(10, WAD, 0, 0, 2 * WAD), u64::MAX);
+        assert_eq!(compute_action_price(10, decrease, 0, 0, 0), u64::MAX);
+        // No decrease, a flat price
+        assert_eq!(compute_action_price(10, 0, 50, 7, 2 * WAD), 10);
+        // Far behind and far ahead of the schedule
+        assert_eq!(compute_action_price(10, decrease, 100_000, 0, 2 * WAD), 0);
+        assert_eq!(
+            compute_action_price(10, decrease, 0, 100_000, 2 * WAD),
+            u64::MAX
+        );
+        assert_eq!(compute_action_price(10, decrease, 0, u64::MAX, 1), u64::MAX);
+        assert_eq!(
+            get_action_cost(&pricing(SHELL), 0, 10, 100_000, u64::MAX),
+            u64::MAX
+        );
+    }
+}
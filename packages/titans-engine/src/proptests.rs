@@ -0,0 +1,110 @@
+// Invariants of the race checked after every action and every turn, for random rules and
+// random orders of the cars
+use proptest::prelude::*;
+
+use crate::{
+    action::{default_actions, ActionSupply},
+    race::{Race, Rules},
+};
+
+const CAR_IDS: [&str; 6] = ["car-0", "car-1", "car-2", "car-3", "car-4", "car-5"];
+
+fn rules() -> impl Strategy<Value = Rules> {
+    (
+        0..5u64,
+        0..2000u64,
+        10..500u64,
+        0..3u64,
+        prop_oneof![Just(ActionSupply::Global), Just(ActionSupply::PerCar)],
+    )
+        .prop_map(
+            |(post_sell_speed, init_balance, target_distance, banana_speed_modifier, supply)| {
+                Rules {
+                    post_sell_speed,
+                    init_balance,
+                    target_distance,
+                    banana_speed_modifier,
+                    actions: default_actions(),
+                    action_supply: supply,
+                }
+            },
+        )
+}
+
+// Orders of each turn, an order is the index of an action of the default actions and an
+// amount, zero amounts and unaffordable orders are part of the game
+fn turns() -> impl Strategy<Value = Vec<Vec<(usize, u64)>>> {
+    let order = (0..default_actions().len(), 0..20u64);
+    prop::collection::vec(prop::collection::vec(order, 0..4), 1..300)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    #[test]
+    fn race_invariants(rules in rules(), num_cars in 2..=CAR_IDS.len(), turns in turns()) {
+        let mut race = Race::new(rules.clone(), &CAR_IDS[..num_cars]);
+
+        for orders in turns {
+            if race.is_over() {
+                break;
+            }
+            let car = race.cars[race.current_seat()].id.clone();
+
+            for (action, amount) in orders {
+                let action = &rules.actions[action].name;
+                let before = race.clone();
+
+                match race.apply_action(&car, action, amount) {
+                    Ok(outcome) => {
+                        // The buyer pays the cost, nobody else is paid or charged
+                        for (after, before) in race.cars.iter().zip(before.cars.iter()) {
+                            if after.id == car {
+                                prop_assert_eq!(after.balance + outcome.cost, before.balance);
+                            } else {
+                                prop_assert_eq!(after.balance, before.balance);
+                            }
+                            prop_assert_eq!(after.y, before.y);
+                        }
+
+                        // A hit car is slowed down to the post shell speed
+                        for shelled in outcome.shelled.iter() {
+                            let car = race.car(shelled).unwrap();
+                            prop_assert_eq!(car.speed, rules.post_sell_speed);
+                        }
+                    }
+                    // A failed action does not change the race
+                    Err(_) => prop_assert_eq!(&race, &before),
+                }
+            }
+
+            let before = race.clone();
+            let winner = race.advance_turn();
+            prop_assert_eq!(race.turns, before.turns + 1);
+            prop_assert_eq!(&winner, &race.winner);
+
+            for (after, before) in race.cars.iter().zip(before.cars.iter()) {
+                prop_assert_eq!(after.balance, before.balance);
+                prop_assert!(after.y >= before.y);
+
+                // A car moving over a banana stops on it and consumes it
+                prop_assert!(!race
+                    .bananas
+                    .iter()
+                    .any(|banana| *banana > before.y && *banana <= after.y));
+            }
+
+            // Exactly one winner once a car reaches the target distance
+            let arrived: Vec<&str> = race
+                .cars
+                .iter()
+                .filter(|car| car.y >= rules.target_distance)
+                .map(|car| car.id.as_str())
+                .collect();
+            match &race.winner {
+                Some(winner) => prop_assert_eq!(arrived, vec![winner.as_str()]),
+                None => prop_assert!(arrived.is_empty()),
+            }
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use cosmwasm_schema::cw_serde;
+
+use crate::{
+    action::{default_actions, ActionConfig, ActionEffect, ActionSupply},
+    error::EngineError,
+    pricing::get_action_cost,
+};
+
+// The rules of a race, the part of the base car config the engine plays by
+#[cw_serde]
+pub struct Rules {
+    // Speed of a car after it is shelled
+    pub post_sell_speed: u64,
+
+    // Initial balance for each car
+    pub init_balance: u64,
+
+    // Target distance
+    pub target_distance: u64,
+
+    // Speed of a car is multiplied by it after hitting a banana
+    pub banana_speed_modifier: u64,
+
+    pub actions: Vec<ActionConfig>,
+
+    pub action_supply: ActionSupply,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            post_sell_speed: 1,
+            init_balance: 17500,
+            target_distance: 1000,
+            banana_speed_modifier: 0,
+            actions: default_actions(),
+            action_supply: ActionSupply::Global,
+        }
+    }
+}
+
+impl Rules {
+    pub fn action(&self, name: &str) -> Option<&ActionConfig> {
+        self.actions.iter().find(|action| action.name == name)
+    }
+}
+
+#[cw_serde]
+pub struct Car {
+    // Address of the car contract, or any unique name outside of the chain
+    pub id: String,
+    pub balance: u64,
+    pub y: u64,
+    pub speed: u64,
+    pub shield: u64,
+}
+
+impl Car {
+    pub fn at_start(id: &str, balance: u64) -> Self {
+        Self {
+            id: id.to_string(),
+            balance,
+            y: 0,
+            speed: 0,
+            shield: 0,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct ActionOutcome {
+    pub cost: u64,
+    // Cars slowed down by a shell or a super shell
+    pub shelled: Vec<String>,
+}
+
+#[cw_serde]
+pub struct Race {
+    pub rules: Rules,
+
+    // Cars in seat order
+    pub cars: Vec<Car>,
+
+    // The bananas in play, tracked by their y position, sorted
+    pub bananas: Vec<u64>,
+
+    pub turns: u64,
+
+    // Sold amount of each action to all the cars
+    pub sold: BTreeMap<String, u64>,
+
+    // Sold amount of each action to each car, by car id
+    pub car_sold: BTreeMap<String, BTreeMap<String, u64>>,
+
+    // The first car reaching the target distance
+    pub winner: Option<String>,
+}
+
+impl Race {
+    pub fn new(rules: Rules, car_ids: &[&str]) -> Self {
+        let cars = car_ids
+            .iter()
+            .map(|id| Car::at_start(id, rules.init_balance))
+            .collect();
+        Self {
+            rules,
+            cars,
+            bananas: vec![],
+            turns: 0,
+            sold: BTreeMap::new(),
+            car_sold: BTreeMap::new(),
+            winner: None,
+        }
+    }
+
+    pub fn car(&self, id: &str) -> Option<&Car> {
+        self.cars.iter().find(|car| car.id == id)
+    }
+
+    // Seat of the car playing the current turn, when the cars take their turns in seat order
+    pub fn current_seat(&self) -> usize {
+        (self.turns % self.cars.len() as u64) as usize
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.winner.is_some()
+    }
+
+    // Amount of an action the price of the car follows, depending on the action supply
+    pub fn sold_for(&self, car: &str, action: &str) -> u64 {
+        match self.rules.action_supply {
+            ActionSupply::Global => self.sold.get(action).copied().unwrap_or_default(),
+            ActionSupply::PerCar => self
+                .car_sold
+                .get(car)
+                .and_then(|sold| sold.get(action))
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+
+    // Cost for the car of buying `amount` of an action at the current turn, a cost above the
+    // balance of the car is only known to be above it
+    pub fn action_cost(&self, car: &str, action: &str, amount: u64) -> Result<u64, EngineError> {
+        let config = self
+            .rules
+            .action(action)
+            .ok_or_else(|| EngineError::UnknownAction {
+                action: action.to_string(),
+            })?;
+        let balance = self
+            .cars
+            .iter()
+            .find(|c| c.id == car)
+            .map(|c| c.balance)
+            .ok_or_else(|| EngineError::UnknownCar {
+                car: car.to_string(),
+            })?;
+        Ok(get_action_cost(
+            &config.pricing,
+            self.turns,
+            amount,
+            self.sold_for(car, action),
+            balance,
+        ))
+    }
+
+    // The car buys `amount` of an action, paid from its balance
+    pub fn apply_action(
+        &mut self,
+        car: &str,
+        action: &str,
+        amount: u64,
+    ) -> Result<ActionOutcome, EngineError> {
+        if amount == 0 {
+            return Err(EngineError::ZeroAmount);
+        }
+
+        let config =
+            self.rules
+                .action(action)
+                .cloned()
+                .ok_or_else(|| EngineError::UnknownAction {
+                    action: action.to_string(),
+                })?;
+
+        // Bananas are dropped one at a time
+        if config.effect == ActionEffect::Banana && amount != 1 {
+            return Err(EngineError::InvalidActionAmount {
+                action: config.name,
+                amount,
+            });
+        }
+
+        let seat =
+            self.cars
+                .iter()
+                .position(|c| c.id == car)
+                .ok_or_else(|| EngineError::UnknownCar {
+                    car: car.to_string(),
+                })?;
+
+        // There is already a banana at our position
+        if config.effect == ActionEffect::Banana && self.bananas.contains(&self.cars[seat].y) {
+            return Ok(ActionOutcome {
+                cost: 0,
+                shelled: vec![],
+            });
+        }
+
+        let cost = self.action_cost(car, action, amount)?;
+        let balance = self.cars[seat].balance;
+        let left = balance
+            .checked_sub(cost)
+            .ok_or(EngineError::InsufficientBalance { balance, cost })?;
+
+        // Checked before anything is paid
+        let overflow = || EngineError::Overflow {
+            car: car.to_string(),
+            action: config.name.clone(),
+        };
+        let speed = match config.effect {
+            ActionEffect::Accelerate => self.cars[seat].speed.checked_add(amount),
+            _ => Some(self.cars[seat].speed),
+        }
+        .ok_or_else(overflow)?;
+        let shield = match config.effect {
+            ActionEffect::Shield => amount
+                .checked_add(1)
+                .and_then(|turns| self.cars[seat].shield.checked_add(turns)),
+            _ => Some(self.cars[seat].shield),
+        }
+        .ok_or_else(overflow)?;
+
+        self.cars[seat].balance = left;
+
+        *self.sold.entry(config.name.clone()).or_default() += amount;
+        *self
+            .car_sold
+            .entry(car.to_string())
+            .or_default()
+            .entry(config.name)
+            .or_default() += amount;
+
+        let shelled = match config.effect {
+            ActionEffect::Accelerate => {
+                self.cars[seat].speed = speed;
+                vec![]
+            }
+            ActionEffect::Shell => self.shell(seat),
+            ActionEffect::SuperShell => self.super_shell(seat),
+            ActionEffect::Banana => {
+                self.bananas.push(self.cars[seat].y);
+                self.bananas.sort_unstable();
+                vec![]
+            }
+            ActionEffect::Shield => {
+                self.cars[seat].shield = shield;
+                vec![]
+            }
+        };
+
+        Ok(ActionOutcome { cost, shelled })
+    }
+
+    // Shell the closest car ahead, a banana in between is hit instead
+    fn shell(&mut self, seat: usize) -> Vec<String> {
+        let y = self.cars[seat].y;
+
+        // Used to determine who to shell.
+        let mut closest_car = None;
+        let mut dis_from_closest_car = u64::MAX;
+
+        for (i, next_car) in self.cars.iter().enumerate() {
+            // If the car is behind or on us, skip it
+            if next_car.y <= y {
+                continue;
+            }
+
+            // If this car is closer than all other cars we've
+            // looked at so far, we'll make it than closest one.
+            let dis_from_next_car = next_car.y - y;
+            if dis_from_next_car < dis_from_closest_car {
+                closest_car = Some(i);
+                dis_from_closest_car = dis_from_next_car
+            }
+        }
+
+        // The first banana in front of us is hit if it is closer or at the same position
+        // as the closest car
+        if let Some(i) = self.bananas.iter().position(|banana| *banana > y) {
+            if closest_car.is_none() || self.bananas[i] <= y + dis_from_closest_car {
+                self.bananas.remove(i);
+                return vec![];
+            }
+        }
+
+        // If there is a closest car, shell it.
+        match closest_car {
+            Some(i)
+                if self.cars[i].shield == 0 && self.cars[i].speed > self.rules.post_sell_speed =>
+            {
+                self.cars[i].speed = self.rules.post_sell_speed;
+                vec![self.cars[i].id.clone()]
+            }
+            _ => vec![],
+        }
+    }
+
+    // Every car in front of us is hit, shields do not protect from super shells
+    fn super_shell(&mut self, seat: usize) -> Vec<String> {
+        let y = self.cars[seat].y;
+        let post_sell_speed = self.rules.post_sell_speed;
+
+        self.cars
+            .iter_mut()
+            .filter(|car| car.y > y && car.speed > post_sell_speed)
+            .map(|car| {
+                car.speed = post_sell_speed;
+                car.id.clone()
+            })
+            .collect()
+    }
+
+    // Move every car by its speed, a car is stopped by the first banana in front of it.
+    // Returns the winner if a car has reached the target distance.
+    pub fn advance_turn(&mut self) -> Option<String> {
+        self.turns += 1;
+
+        for car in self.cars.iter_mut() {
+            if car.shield > 0 {
+                car.shield -= 1;
+            }
+
+            let mut target_position = car.y + car.speed;
+
+            // Bananas are sorted, only the first one in front of the car matters
+            if let Some(i) = self.bananas.iter().position(|banana| *banana > car.y) {
+                if target_position >= self.bananas[i] {
+                    target_position = self.bananas[i];
+                    car.speed *= self.rules.banana_speed_modifier;
+                    self.bananas.remove(i);
+                }
+            }
+
+            car.y = target_position;
+
+            if car.y >= self.rules.target_distance {
+                self.winner = Some(car.id.clone());
+                return self.winner.clone();
+            }
+        }
+
+        None
+    }
+
+    // Cars from the first to the last one, cars at the same position keep their seat order
+    pub fn standings(&self) -> Vec<&Car> {
+        let mut cars: Vec<&Car> = self.cars.iter().collect();
+        cars.sort_by_key(|car| Reverse(car.y));
+        cars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Race, Rules};
+    use crate::{EngineError, ACCELERATE, BANANA, SHELL, SHIELD, SUPER_SHELL};
+
+    fn race() -> Race {
+        Race::new(Rules::default(), &["car1", "car2", "car3"])
+    }
+
+    #[test]
+    fn test_apply_action() {
+        let mut race = race();
+
+        // Ahead of the schedule at the first turn: 12 + 14 + 18
+        let outcome = race.apply_action("car1", ACCELERATE, 3).unwrap();
+        assert_eq!(outcome.cost, 44);
+        assert_eq!(race.cars[0].speed, 3);
+        assert_eq!(race.cars[0].balance, 17500 - 44);
+        assert_eq!(race.sold[ACCELERATE], 3);
+        assert_eq!(race.car_sold["car1"][ACCELERATE], 3);
+
+        race.apply_action("car2", SHIELD, 1).unwrap();
+        assert_eq!(race.cars[1].shield, 2);
+
+        assert_eq!(
+            race.apply_action("car1", "rocket", 1),
+            Err(EngineError::UnknownAction {
+                action: "rocket".to_string()
+            })
+        );
+        assert_eq!(
+            race.apply_action("car4", ACCELERATE, 1),
+            Err(EngineError::UnknownCar {
+                car: "car4".to_string()
+            })
+        );
+        assert_eq!(
+            race.apply_action("car1", BANANA, 2),
+            Err(EngineError::InvalidActionAmount {
+                action: BANANA.to_string(),
+                amount: 2
+            })
+        );
+        assert_eq!(
+            race.apply_action("car1", ACCELERATE, 0),
+            Err(EngineError::ZeroAmount)
+        );
+
+        race.cars[2].balance = 0;
+        assert_eq!(
+            race.apply_action("car3", ACCELERATE, 1),
+            Err(EngineError::InsufficientBalance {
+                balance: 0,
+                cost: 22
+            })
+        );
+
+        // The cost of a huge amount stops at the first unit past the balance: 22 + 27
+        race.cars[2].balance = 40;
+        assert_eq!(
+            race.apply_action("car3", ACCELERATE, u64::MAX),
+            Err(EngineError::InsufficientBalance {
+                balance: 40,
+                cost: 49
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_action_overflow() {
+        let mut race = race();
+        race.cars[0].speed = u64::MAX;
+        race.cars[1].shield = u64::MAX - 1;
+        assert_eq!(
+            race.apply_action("car1", ACCELERATE, 1),
+            Err(EngineError::Overflow {
+                car: "car1".to_string(),
+                action: ACCELERATE.to_string()
+            })
+        );
+        assert_eq!(
+            race.apply_action("car2", SHIELD, 1),
+            Err(EngineError::Overflow {
+                car: "car2".to_string(),
+                action: SHIELD.to_string()
+            })
+        );
+        // Nothing is paid
+        assert_eq!(race.cars[0].balance, 17500);
+        assert_eq!(race.cars[1].balance, 17500);
+        assert!(race.sold.is_empty());
+    }
+
+    #[test]
+    fn test_shell() {
+        let mut race = race();
+        race.cars[1].y = 10;
+        race.cars[1].speed = 5;
+        race.cars[2].y = 20;
+        race.cars[2].speed = 5;
+        // Shells bought in a row are far ahead of their schedule
+        for car in race.cars.iter_mut() {
+            car.balance = 1_000_000;
+        }
+
+        // Only the closest car ahead is hit
+        let outcome = race.apply_action("car1", SHELL, 1).unwrap();
+        assert_eq!(outcome.shelled, vec!["car2".to_string()]);
+        assert_eq!(race.cars[1].speed, 1);
+        assert_eq!(race.cars[2].speed, 5);
+
+        // A banana in between is hit instead
+        race.bananas = vec![15];
+        let outcome = race.apply_action("car2", SHELL, 1).unwrap();
+        assert!(outcome.shelled.is_empty());
+        assert!(race.bananas.is_empty());
+        assert_eq!(race.cars[2].speed, 5);
+
+        // Shields protect from shells, not from super shells
+        race.cars[2].shield = 1;
+        let outcome = race.apply_action("car2", SHELL, 1).unwrap();
+        assert!(outcome.shelled.is_empty());
+        race.cars[1].speed = 5;
+        let outcome = race.apply_action("car1", SUPER_SHELL, 1).unwrap();
+        assert_eq!(
+            outcome.shelled,
+            vec!["car2".to_string(), "car3".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_advance_turn() {
+        let mut race = race();
+        race.cars[0].speed = 10;
+        race.cars[1].speed = 10;
+        race.cars[1].y = 5;
+        race.bananas = vec![8];
+
+        assert_eq!(race.advance_turn(), None);
+        assert_eq!(race.turns, 1);
+
+        // car1 is stopped by the banana, car2 is already past it
+        assert_eq!(race.cars[0].y, 8);
+        assert_eq!(race.cars[0].speed, 0);
+        assert_eq!(race.cars[1].y, 15);
+        assert!(race.bananas.is_empty());
+
+        let ids: Vec<&str> = race.standings().iter().map(|car| car.id.as_str()).collect();
+        assert_eq!(ids, vec!["car2", "car1", "car3"]);
+
+        race.cars[1].y = 995;
+        assert_eq!(race.advance_turn(), Some("car2".to_string()));
+        assert!(race.is_over());
+    }
+}
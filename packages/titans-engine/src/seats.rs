@@ -0,0 +1,22 @@
+use sha2::{Digest, Sha256};
+
+// Fisher-Yates shuffle of the seats of a round, each swap is drawn from
+// sha256(seed | round | index) so anyone can recompute the order from the seed
+pub fn shuffle_seats(seed: &str, round: u64, num_seats: usize) -> Vec<usize> {
+    let mut seats: Vec<usize> = (0..num_seats).collect();
+
+    for i in (1..num_seats).rev() {
+        let mut hasher = Sha256::new();
+        hasher.update(seed.as_bytes());
+        hasher.update(round.to_be_bytes());
+        hasher.update((i as u64).to_be_bytes());
+        let hash = hasher.finalize();
+
+        let mut rand = [0u8; 8];
+        rand.copy_from_slice(&hash[..8]);
+        let j = (u64::from_be_bytes(rand) % (i as u64 + 1)) as usize;
+        seats.swap(i, j);
+    }
+
+    seats
+}
@@ -0,0 +1,113 @@
+use std::fs;
+
+use cosmwasm_std::Addr;
+use titans_car_sdk::params::ParamsCarInstantiateMsg;
+use titans_engine::Rules;
+use titans_sim::{builtin, evolve, Args, EvolveConfig, SimConfig, Strategy};
+
+const USAGE: &str = "Usage: titans-evolve [--population N] [--generations N] [--mutation-rate P] [--elites N] [--seed N]
+       [--races N] [--max-turns N] [--rules rules.json] [--preset presets/NAME.json]
+       [--base-car-addr ADDR] [--out params.json] [OPPONENT...]
+
+Evolves the parameters of the parameterized car against the opponents and writes the best
+ones as the InstantiateMsg of the car.
+Opponents: accelerator, sheller, defender, parameterized (default: sheller defender, 1 to 7 cars)";
+
+const OPPONENTS: [&str; 2] = ["sheller", "defender"];
+
+fn main() {
+    let mut config = EvolveConfig {
+        sim: SimConfig {
+            rules: Rules::default(),
+            races: 200,
+            max_turns: 1000,
+            seed: 0,
+        },
+        population: 24,
+        generations: 20,
+        mutation_rate: 0.2,
+        elites: 2,
+        seed: 0,
+    };
+    let mut base_car_addr = "BASE_CAR_ADDR".to_string();
+    let mut out = "params.json".to_string();
+    let mut names = vec![];
+    let mut num_players = None;
+
+    let mut args = Args::from_env(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--population" => config.population = args.value(&arg),
+            "--generations" => config.generations = args.value(&arg),
+            "--mutation-rate" => config.mutation_rate = args.value(&arg),
+            "--elites" => config.elites = args.value(&arg),
+            "--seed" => {
+                config.seed = args.value(&arg);
+                config.sim.seed = config.seed;
+            }
+            "--races" => config.sim.races = args.value(&arg),
+            "--max-turns" => config.sim.max_turns = args.value(&arg),
+            "--rules" => config.sim.rules = args.json(&arg),
+            "--preset" => {
+                let preset = args.preset(&arg).config;
+                config.sim.rules = preset.rules();
+                num_players = Some(preset.num_players as usize);
+            }
+            "--base-car-addr" => base_car_addr = args.value(&arg),
+            "--out" => out = args.value(&arg),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            name => names.push(name.to_string()),
+        }
+    }
+
+    if !(0.0..=1.0).contains(&config.mutation_rate) {
+        args.exit("the mutation rate is a probability between 0 and 1");
+    }
+    if names.is_empty() {
+        let count = num_players.map_or(OPPONENTS.len(), |n| n.saturating_sub(1));
+        names = OPPONENTS
+            .iter()
+            .cycle()
+            .take(count)
+            .map(|name| name.to_string())
+            .collect();
+    }
+    if num_players.is_some_and(|num_players| num_players != names.len() + 1) {
+        args.exit("the number of opponents does not match num_players of the preset");
+    }
+    if names.is_empty() || names.len() > 7 {
+        args.exit("a race needs between 1 and 7 opponents");
+    }
+    let mut opponents: Vec<Box<dyn Strategy>> = names
+        .iter()
+        .map(|name| {
+            builtin(name).unwrap_or_else(|| args.exit(&format!("unknown strategy {}", name)))
+        })
+        .collect();
+
+    let report = evolve(&config, &mut opponents);
+    for (generation, fitness) in report.history.iter().enumerate() {
+        println!(
+            "generation {:>3}  best win rate {:>6.1}%",
+            generation,
+            fitness * 100.0
+        );
+    }
+    println!(
+        "best: win rate {:.1}%, avg rank {:.2} against {}",
+        report.best.fitness * 100.0,
+        report.best.avg_rank,
+        names.join(" ")
+    );
+
+    let msg = ParamsCarInstantiateMsg {
+        base_car_addr: Addr::unchecked(base_car_addr),
+        params: report.best.params,
+    };
+    let data = serde_json::to_string_pretty(&msg).unwrap();
+    fs::write(&out, data).unwrap_or_else(|err| args.exit(&err.to_string()));
+    println!("InstantiateMsg written to {}", out);
+}
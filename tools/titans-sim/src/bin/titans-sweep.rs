@@ -0,0 +1,96 @@
+use std::fs;
+
+use titans_engine::{pricing::WAD, Rules};
+use titans_sim::{
+    builtin, parse_range, parse_wad_range, sweep, to_csv, Args, SimConfig, Strategy, Sweep,
+};
+
+const USAGE: &str = "Usage: titans-sweep [--races N] [--max-turns N] [--seed N] [--rules rules.json] [--preset presets/NAME.json]
+       [--target-price RANGE] [--per-turn-decrease RANGE] [--sell-per-turn RANGE] [--action NAME]... [--out sweep.csv] [STRATEGY...]
+
+Ranges: 10,20,40 or START:END:STEP (end included), decimals such as 0.2,0.33 or 0.1:0.5:0.1
+for --per-turn-decrease and --sell-per-turn. The pricing of the actions is set to each
+combination of the ranges, for all the actions or only the ones given with --action, a field
+without range keeps the pricing of each action.
+Strategies: accelerator, sheller, defender (default: all of them, 2 to 8 cars)";
+
+const STRATEGIES: [&str; 3] = ["accelerator", "sheller", "defender"];
+
+fn main() {
+    let mut config = SimConfig {
+        rules: Rules::default(),
+        races: 1000,
+        max_turns: 1000,
+        seed: 0,
+    };
+    let mut ranges = Sweep {
+        target_price: vec![],
+        per_turn_decrease: vec![],
+        sell_per_turn: vec![],
+        actions: vec![],
+    };
+    let mut out = None;
+    let mut names = vec![];
+    let mut num_players = None;
+
+    let mut args = Args::from_env(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--races" => config.races = args.value(&arg),
+            "--max-turns" => config.max_turns = args.value(&arg),
+            "--seed" => config.seed = args.value(&arg),
+            "--rules" => config.rules = args.json(&arg),
+            "--preset" => {
+                let preset = args.preset(&arg).config;
+                config.rules = preset.rules();
+                num_players = Some(preset.num_players as usize);
+            }
+            "--target-price" => ranges.target_price = args.parse(&arg, parse_range),
+            "--per-turn-decrease" => ranges.per_turn_decrease = args.parse(&arg, parse_wad_range),
+            "--sell-per-turn" => ranges.sell_per_turn = args.parse(&arg, parse_wad_range),
+            "--action" => ranges.actions.push(args.value(&arg)),
+            "--out" => out = Some(args.value::<String>(&arg)),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            name => names.push(name.to_string()),
+        }
+    }
+
+    for action in ranges.actions.iter() {
+        if config.rules.action(action).is_none() {
+            args.exit(&format!("unknown action {}", action));
+        }
+    }
+    if ranges.per_turn_decrease.iter().any(|d| *d >= WAD) || ranges.sell_per_turn.contains(&0) {
+        args.exit("the per turn decrease has to be below 1 and the sell per turn above 0");
+    }
+    if names.is_empty() {
+        let count = num_players.unwrap_or(STRATEGIES.len());
+        names = STRATEGIES
+            .iter()
+            .cycle()
+            .take(count)
+            .map(|name| name.to_string())
+            .collect();
+    }
+    if num_players.is_some_and(|num_players| num_players != names.len()) {
+        args.exit("the number of strategies does not match num_players of the preset");
+    }
+    if names.len() < 2 || names.len() > 8 {
+        args.exit("a race needs between 2 and 8 cars");
+    }
+    let mut strategies: Vec<Box<dyn Strategy>> = names
+        .iter()
+        .map(|name| {
+            builtin(name).unwrap_or_else(|| args.exit(&format!("unknown strategy {}", name)))
+        })
+        .collect();
+
+    let csv = to_csv(&sweep(&config, &ranges, &mut strategies));
+    match out {
+        Some(path) => fs::write(&path, csv).unwrap_or_else(|err| args.exit(&err.to_string())),
+        None => print!("{}", csv),
+    }
+}
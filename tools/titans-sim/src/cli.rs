@@ -0,0 +1,116 @@
+use std::{env, fs, iter::Skip, path::Path, process, str::FromStr};
+
+use serde::de::DeserializeOwned;
+use titans_car_sdk::types::Config;
+
+// A base-car config saved as a JSON file, named after the file
+pub struct Preset {
+    pub name: String,
+    pub config: Config,
+}
+
+// Arguments of a command line tool, an invalid one exits with the usage of the tool
+pub struct Args<I> {
+    args: I,
+    usage: &'static str,
+}
+
+impl Args<Skip<env::Args>> {
+    pub fn from_env(usage: &'static str) -> Self {
+        Self::new(env::args().skip(1), usage)
+    }
+}
+
+impl<I: Iterator<Item = String>> Args<I> {
+    pub fn new(args: I, usage: &'static str) -> Self {
+        Self { args, usage }
+    }
+
+    // Value of the option `arg`, parsed with `parse`
+    pub fn parse<T>(&mut self, arg: &str, parse: impl Fn(&str) -> Option<T>) -> T {
+        self.args
+            .next()
+            .and_then(|value| parse(&value))
+            .unwrap_or_else(|| self.exit(&format!("invalid value for {}", arg)))
+    }
+
+    pub fn value<T: FromStr>(&mut self, arg: &str) -> T {
+        self.parse(arg, |value| value.parse().ok())
+    }
+
+    // The value of the option `arg` is the path of a JSON file
+    pub fn json<T: DeserializeOwned>(&mut self, arg: &str) -> T {
+        let path: String = self.value(arg);
+        read_json(&path).unwrap_or_else(|err| self.exit(&err))
+    }
+
+    pub fn preset(&mut self, arg: &str) -> Preset {
+        let path: String = self.value(arg);
+        read_preset(&path).unwrap_or_else(|err| self.exit(&err))
+    }
+
+    pub fn exit(&self, message: &str) -> ! {
+        exit(self.usage, message)
+    }
+}
+
+impl<I: Iterator<Item = String>> Iterator for Args<I> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.args.next()
+    }
+}
+
+// Prints the error and the usage of the tool
+pub fn exit(usage: &str, message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, usage);
+    process::exit(1)
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let data = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    serde_json::from_str(&data).map_err(|err| format!("{}: {}", path, err))
+}
+
+pub fn read_preset(path: &str) -> Result<Preset, String> {
+    let name = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+    Ok(Preset {
+        name,
+        config: read_json(path)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use titans_engine::Rules;
+
+    use super::{read_json, read_preset, Args};
+
+    const USAGE: &str = "Usage: test";
+
+    #[test]
+    fn test_args() {
+        let argv = ["--races", "12", "--seed", "7", "car"];
+        let mut args = Args::new(argv.iter().map(|arg| arg.to_string()), USAGE);
+        assert_eq!(args.next().as_deref(), Some("--races"));
+        assert_eq!(args.value::<u64>("--races"), 12);
+        assert_eq!(args.next().as_deref(), Some("--seed"));
+        assert_eq!(args.parse("--seed", |value| value.parse::<u64>().ok()), 7);
+        assert_eq!(args.collect::<Vec<_>>(), vec!["car".to_string()]);
+    }
+
+    #[test]
+    fn test_read_preset() {
+        let preset = read_preset("../../presets/sprint.json").unwrap();
+        assert_eq!(preset.name, "sprint");
+        assert!(preset.config.num_players >= 2);
+
+        let err = read_json::<Rules>("../../presets/missing.json").unwrap_err();
+        assert!(err.starts_with("../../presets/missing.json: "));
+        assert!(read_preset("Cargo.toml").is_err());
+    }
+}
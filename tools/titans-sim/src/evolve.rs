@@ -0,0 +1,229 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use titans_car_sdk::StrategyParams;
+
+use crate::{
+    sim::{simulate, SimConfig},
+    strategy::{Parameterized, Strategy},
+};
+
+// Each parameter is a gene with its bounds, in the order of `to_genes`
+pub const GENES: [(&str, u64, u64); 10] = [
+    ("reserve_per_mille", 0, 900),
+    ("budget_per_mille", 1, 1000),
+    ("target_speed", 1, 60),
+    ("max_accelerate", 1, 20),
+    ("shell_min_rank", 0, 7),
+    ("shell_speed_gap", 0, 20),
+    ("super_shell_min_rank", 0, 7),
+    ("banana_distance", 0, 200),
+    ("shield_distance", 0, 200),
+    ("sprint_distance", 0, 1000),
+];
+
+pub fn to_genes(params: &StrategyParams) -> [u64; 10] {
+    [
+        params.reserve_per_mille,
+        params.budget_per_mille,
+        params.target_speed,
+        params.max_accelerate,
+        params.shell_min_rank,
+        params.shell_speed_gap,
+        params.super_shell_min_rank,
+        params.banana_distance,
+        params.shield_distance,
+        params.sprint_distance,
+    ]
+}
+
+pub fn from_genes(genes: &[u64; 10]) -> StrategyParams {
+    StrategyParams {
+        reserve_per_mille: genes[0],
+        budget_per_mille: genes[1],
+        target_speed: genes[2],
+        max_accelerate: genes[3],
+        shell_min_rank: genes[4],
+        shell_speed_gap: genes[5],
+        super_shell_min_rank: genes[6],
+        banana_distance: genes[7],
+        shield_distance: genes[8],
+        sprint_distance: genes[9],
+    }
+}
+
+pub struct EvolveConfig {
+    // Races of each evaluation, always with the same seats so the candidates are compared
+    // on the same races
+    pub sim: SimConfig,
+    pub population: usize,
+    pub generations: usize,
+    // Chance of each gene to mutate
+    pub mutation_rate: f64,
+    // Best candidates kept as they are in the next generation
+    pub elites: usize,
+    // Seed of the population, the same seed evolves the same parameters
+    pub seed: u64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub params: StrategyParams,
+    // Win rate against the opponents
+    pub fitness: f64,
+    pub avg_rank: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct EvolveReport {
+    pub best: Candidate,
+    // Best fitness of each generation
+    pub history: Vec<f64>,
+}
+
+// Win rate of the parameters racing against the opponents, in the first seat of the list
+pub fn evaluate(
+    config: &SimConfig,
+    params: &StrategyParams,
+    opponents: &mut Vec<Box<dyn Strategy>>,
+) -> Candidate {
+    opponents.insert(
+        0,
+        Box::new(Parameterized {
+            name: "candidate".to_string(),
+            params: params.clone(),
+        }),
+    );
+    let report = simulate(config, opponents);
+    opponents.remove(0);
+
+    Candidate {
+        params: params.clone(),
+        fitness: report.strategies[0].win_rate,
+        avg_rank: report.strategies[0].avg_rank,
+    }
+}
+
+// Genetic algorithm over the parameters: tournament selection, uniform crossover, mutation
+// within the bounds of each gene, and elitism. The default parameters are part of the
+// first generation.
+pub fn evolve(config: &EvolveConfig, opponents: &mut Vec<Box<dyn Strategy>>) -> EvolveReport {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut population = vec![to_genes(&StrategyParams::default())];
+    while population.len() < config.population.max(2) {
+        population.push(GENES.map(|(_, min, max)| rng.gen_range(min..=max)));
+    }
+
+    let mut history = vec![];
+    let mut ranked = vec![];
+    for generation in 0..config.generations.max(1) {
+        ranked = population
+            .iter()
+            .map(|genes| evaluate(&config.sim, &from_genes(genes), opponents))
+            .collect::<Vec<Candidate>>();
+        // Higher win rate first, then lower average rank
+        ranked.sort_by(|a, b| {
+            b.fitness
+                .total_cmp(&a.fitness)
+                .then(a.avg_rank.total_cmp(&b.avg_rank))
+        });
+        history.push(ranked[0].fitness);
+
+        if generation + 1 == config.generations.max(1) {
+            break;
+        }
+
+        let mut next: Vec<[u64; 10]> = ranked
+            .iter()
+            .take(config.elites.min(ranked.len()))
+            .map(|candidate| to_genes(&candidate.params))
+            .collect();
+        while next.len() < population.len() {
+            let a = to_genes(&tournament(&ranked, &mut rng).params);
+            let b = to_genes(&tournament(&ranked, &mut rng).params);
+            let mut child = [0u64; 10];
+            for (i, (_, min, max)) in GENES.iter().enumerate() {
+                child[i] = if rng.gen_bool(0.5) { a[i] } else { b[i] };
+                if rng.gen_bool(config.mutation_rate) {
+                    // A step of at most a tenth of the bounds
+                    let step = ((max - min) / 10).max(1) as i64;
+                    let value = child[i] as i64 + rng.gen_range(-step..=step);
+                    child[i] = value.clamp(*min as i64, *max as i64) as u64;
+                }
+            }
+            next.push(child);
+        }
+        population = next;
+    }
+
+    EvolveReport {
+        best: ranked.swap_remove(0),
+        history,
+    }
+}
+
+// The best of three random candidates
+fn tournament<'a>(ranked: &'a [Candidate], rng: &mut StdRng) -> &'a Candidate {
+    // The candidates are ranked, the lowest index is the best
+    (0..3)
+        .map(|_| rng.gen_range(0..ranked.len()))
+        .min()
+        .map(|i| &ranked[i])
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use titans_car_sdk::StrategyParams;
+    use titans_engine::Rules;
+
+    use super::{evolve, from_genes, to_genes, EvolveConfig, GENES};
+    use crate::{
+        sim::SimConfig,
+        strategy::{builtin, Strategy},
+    };
+
+    fn opponents() -> Vec<Box<dyn Strategy>> {
+        ["sheller", "defender"]
+            .iter()
+            .map(|name| builtin(name).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_genes() {
+        let params = StrategyParams::default();
+        assert_eq!(from_genes(&to_genes(&params)), params);
+        for ((_, min, max), gene) in GENES.iter().zip(to_genes(&params)) {
+            assert!(gene >= *min && gene <= *max);
+        }
+    }
+
+    #[test]
+    fn test_evolve() {
+        let config = EvolveConfig {
+            sim: SimConfig {
+                rules: Rules::default(),
+                races: 10,
+                max_turns: 1000,
+                seed: 1,
+            },
+            population: 6,
+            generations: 3,
+            mutation_rate: 0.3,
+            elites: 1,
+            seed: 5,
+        };
+        let report = evolve(&config, &mut opponents());
+
+        // The best candidate is kept, the same races are played by each generation
+        assert_eq!(report.history.len(), 3);
+        assert!(report.history.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(report.best.fitness, report.history[2]);
+        for ((_, min, max), gene) in GENES.iter().zip(to_genes(&report.best.params)) {
+            assert!(gene >= *min && gene <= *max);
+        }
+
+        // The same seed evolves the same parameters
+        assert_eq!(report, evolve(&config, &mut opponents()));
+    }
+}
@@ -0,0 +1,13 @@
+pub mod cli;
+pub mod evolve;
+pub mod sim;
+pub mod strategy;
+pub mod sweep;
+
+pub use crate::cli::{read_json, read_preset, Args, Preset};
+pub use crate::evolve::{evolve, Candidate, EvolveConfig, EvolveReport};
+pub use crate::sim::{simulate, SimConfig, SimReport, StrategyReport};
+pub use crate::strategy::{builtin, Order, Parameterized, Strategy, TurnView};
+pub use crate::sweep::{
+    parse_range, parse_wad_range, sweep, to_csv, PricingOverride, Sweep, SweepRow,
+};
@@ -0,0 +1,99 @@
+use titans_engine::Rules;
+use titans_sim::{builtin, simulate, Args, SimConfig, SimReport, Strategy};
+
+const USAGE: &str = "Usage: titans-sim [--races N] [--max-turns N] [--seed N] [--rules rules.json] [--preset presets/NAME.json] [--json] [STRATEGY...]
+
+Strategies: accelerator, sheller, defender (default: all of them, 2 to 8 cars)
+A preset is a base-car config, it sets the rules and the number of cars";
+
+const STRATEGIES: [&str; 3] = ["accelerator", "sheller", "defender"];
+
+fn main() {
+    let mut config = SimConfig {
+        rules: Rules::default(),
+        races: 10_000,
+        max_turns: 1000,
+        seed: 0,
+    };
+    let mut json = false;
+    let mut names = vec![];
+    let mut num_players = None;
+
+    let mut args = Args::from_env(USAGE);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--races" => config.races = args.value(&arg),
+            "--max-turns" => config.max_turns = args.value(&arg),
+            "--seed" => config.seed = args.value(&arg),
+            "--rules" => config.rules = args.json(&arg),
+            "--preset" => {
+                let preset = args.preset(&arg).config;
+                config.rules = preset.rules();
+                num_players = Some(preset.num_players as usize);
+            }
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            name => names.push(name.to_string()),
+        }
+    }
+
+    if names.is_empty() {
+        let count = num_players.unwrap_or(STRATEGIES.len());
+        names = STRATEGIES
+            .iter()
+            .cycle()
+            .take(count)
+            .map(|name| name.to_string())
+            .collect();
+    }
+    if num_players.is_some_and(|num_players| num_players != names.len()) {
+        args.exit("the number of strategies does not match num_players of the preset");
+    }
+    if names.len() < 2 || names.len() > 8 {
+        args.exit("a race needs between 2 and 8 cars");
+    }
+    let mut strategies: Vec<Box<dyn Strategy>> = names
+        .iter()
+        .map(|name| {
+            builtin(name).unwrap_or_else(|| args.exit(&format!("unknown strategy {}", name)))
+        })
+        .collect();
+
+    let report = simulate(&config, &mut strategies);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_report(&report);
+    }
+}
+
+fn print_report(report: &SimReport) {
+    println!(
+        "{} races, {} without winner",
+        report.races, report.unfinished
+    );
+    println!(
+        "{:<12} {:>8} {:>9} {:>12} {:>9} {:>10}  spend per race",
+        "strategy", "wins", "win rate", "finish turn", "avg rank", "rank var"
+    );
+    for strategy in report.strategies.iter() {
+        let spend: Vec<String> = strategy
+            .spend_per_action
+            .iter()
+            .map(|(action, spend)| format!("{}={:.1}", action, spend))
+            .collect();
+        println!(
+            "{:<12} {:>8} {:>8.1}% {:>12.1} {:>9.2} {:>10.3}  {}",
+            strategy.name,
+            strategy.wins,
+            strategy.win_rate * 100.0,
+            strategy.avg_finish_turn,
+            strategy.avg_rank,
+            strategy.rank_variance,
+            spend.join(" ")
+        );
+    }
+}
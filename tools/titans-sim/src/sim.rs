@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::Serialize;
+use titans_engine::{Race, Rules};
+
+use crate::strategy::{Strategy, TurnView};
+
+pub struct SimConfig {
+    pub rules: Rules,
+    pub races: u64,
+    // Races still running after `max_turns` turns have no winner
+    pub max_turns: u64,
+    // Seed of the seats shuffle, the same seed plays the same races
+    pub seed: u64,
+}
+
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct StrategyReport {
+    pub name: String,
+    pub wins: u64,
+    pub win_rate: f64,
+    // Turn the race ended at, over the races won
+    pub avg_finish_turn: f64,
+    // Rank at the end of the race, 1 is the winner
+    pub avg_rank: f64,
+    pub rank_variance: f64,
+    // Average spend per race of each action
+    pub spend_per_action: BTreeMap<String, f64>,
+    // Turns lost because one of the orders failed
+    pub failed_turns: u64,
+}
+
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct SimReport {
+    pub races: u64,
+    // Races without winner after the max turns
+    pub unfinished: u64,
+    pub strategies: Vec<StrategyReport>,
+}
+
+#[derive(Default)]
+struct Totals {
+    wins: u64,
+    finish_turns: u64,
+    ranks: Vec<u64>,
+    spend: BTreeMap<String, u64>,
+    failed_turns: u64,
+}
+
+// Play `config.races` races between the strategies, the seats are shuffled for each race
+pub fn simulate(config: &SimConfig, strategies: &mut [Box<dyn Strategy>]) -> SimReport {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut totals: Vec<Totals> = strategies.iter().map(|_| Totals::default()).collect();
+    let mut unfinished = 0;
+
+    // Car ids are the index of the strategy
+    let ids: Vec<String> = (0..strategies.len()).map(|i| i.to_string()).collect();
+    let mut seats: Vec<usize> = (0..strategies.len()).collect();
+
+    for _ in 0..config.races {
+        seats.shuffle(&mut rng);
+        let car_ids: Vec<&str> = seats.iter().map(|i| ids[*i].as_str()).collect();
+        let mut race = Race::new(config.rules.clone(), &car_ids);
+        for strategy in strategies.iter_mut() {
+            strategy.reset();
+        }
+
+        while !race.is_over() && race.turns < config.max_turns {
+            let index = seats[race.current_seat()];
+            let car = ids[index].clone();
+            let orders = strategies[index].take_turn(&TurnView {
+                race: &race,
+                car: &car,
+            });
+
+            // Orders are applied all together or not at all
+            let mut next = race.clone();
+            let mut spend = vec![];
+            let mut failed = false;
+            for (action, amount) in orders {
+                match next.apply_action(&car, &action, amount) {
+                    Ok(outcome) => spend.push((action, outcome.cost)),
+                    Err(_) => {
+                        failed = true;
+                        break;
+                    }
+                }
+            }
+
+            if failed {
+                totals[index].failed_turns += 1;
+            } else {
+                race = next;
+                for (action, cost) in spend {
+                    *totals[index].spend.entry(action).or_default() += cost;
+                }
+            }
+
+            race.advance_turn();
+        }
+
+        match &race.winner {
+            Some(winner) => {
+                let index: usize = winner.parse().expect("car id is a strategy index");
+                totals[index].wins += 1;
+                totals[index].finish_turns += race.turns;
+            }
+            None => unfinished += 1,
+        }
+        for (rank, car) in race.standings().iter().enumerate() {
+            let index: usize = car.id.parse().expect("car id is a strategy index");
+            totals[index].ranks.push(rank as u64 + 1);
+        }
+    }
+
+    SimReport {
+        races: config.races,
+        unfinished,
+        strategies: strategies
+            .iter()
+            .zip(totals)
+            .map(|(strategy, totals)| report(strategy.name(), config.races, totals))
+            .collect(),
+    }
+}
+
+fn report(name: &str, races: u64, totals: Totals) -> StrategyReport {
+    let ratio = |value: u64, count: u64| {
+        if count == 0 {
+            0.0
+        } else {
+            value as f64 / count as f64
+        }
+    };
+
+    let avg_rank = ratio(totals.ranks.iter().sum(), totals.ranks.len() as u64);
+    let rank_variance = if totals.ranks.is_empty() {
+        0.0
+    } else {
+        totals
+            .ranks
+            .iter()
+            .map(|rank| (*rank as f64 - avg_rank).powi(2))
+            .sum::<f64>()
+            / totals.ranks.len() as f64
+    };
+
+    StrategyReport {
+        name: name.to_string(),
+        wins: totals.wins,
+        win_rate: ratio(totals.wins, races),
+        avg_finish_turn: ratio(totals.finish_turns, totals.wins),
+        avg_rank,
+        rank_variance,
+        spend_per_action: totals
+            .spend
+            .into_iter()
+            .map(|(action, spend)| (action, ratio(spend, races)))
+            .collect(),
+        failed_turns: totals.failed_turns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use titans_engine::Rules;
+
+    use super::{simulate, SimConfig};
+    use crate::strategy::{builtin, Strategy};
+
+    fn strategies() -> Vec<Box<dyn Strategy>> {
+        ["accelerator", "sheller", "defender"]
+            .iter()
+            .map(|name| builtin(name).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_simulate() {
+        let config = SimConfig {
+            rules: Rules::default(),
+            races: 200,
+            max_turns: 1000,
+            seed: 7,
+        };
+        let report = simulate(&config, &mut strategies());
+
+        assert_eq!(report.strategies.len(), 3);
+        let wins: u64 = report.strategies.iter().map(|s| s.wins).sum();
+        assert_eq!(wins + report.unfinished, 200);
+        for strategy in report.strategies.iter() {
+            assert!(strategy.avg_rank >= 1.0 && strategy.avg_rank <= 3.0);
+            assert!(strategy.spend_per_action["accelerate"] > 0.0);
+        }
+
+        // The same seed plays the same races
+        assert_eq!(report, simulate(&config, &mut strategies()));
+    }
+}
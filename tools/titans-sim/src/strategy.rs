@@ -0,0 +1,151 @@
+use titans_car_sdk::{StrategyParams, TurnInfo};
+use titans_engine::{Car, Race, ACCELERATE, BANANA, SHELL, SHIELD};
+
+// An action to buy, applied in order at the end of the turn
+pub type Order = (String, u64);
+
+// What a car contract can see during its turn: every car, the bananas and the prices
+pub struct TurnView<'a> {
+    pub race: &'a Race,
+    pub car: &'a str,
+}
+
+impl<'a> TurnView<'a> {
+    pub fn me(&self) -> &'a Car {
+        self.race.car(self.car).expect("car is in the race")
+    }
+
+    // Cars from the first to the last one
+    pub fn standings(&self) -> Vec<&'a Car> {
+        self.race.standings()
+    }
+
+    // Rank of the car, 0 is the leader
+    pub fn rank(&self) -> usize {
+        self.standings()
+            .iter()
+            .position(|car| car.id == self.car)
+            .unwrap_or_default()
+    }
+
+    // Cost of buying `amount` of an action at the current turn, None if it is not enabled
+    pub fn price(&self, action: &str, amount: u64) -> Option<u64> {
+        self.race.action_cost(self.car, action, amount).ok()
+    }
+
+    pub fn can_afford(&self, action: &str, amount: u64) -> bool {
+        self.price(action, amount)
+            .is_some_and(|cost| cost <= self.me().balance)
+    }
+}
+
+// A car strategy, the equivalent of the `TakeTurn` handler of a car contract. If any of
+// the orders fails, none of them is applied and the car loses its turn, as the base
+// contract does when a car errors.
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    // Called before each race
+    fn reset(&mut self) {}
+
+    fn take_turn(&mut self, view: &TurnView) -> Vec<Order>;
+}
+
+// Accelerate every turn, as long as it is affordable
+pub struct Accelerator;
+
+impl Strategy for Accelerator {
+    fn name(&self) -> &str {
+        "accelerator"
+    }
+
+    fn take_turn(&mut self, view: &TurnView) -> Vec<Order> {
+        if view.can_afford(ACCELERATE, 1) {
+            return vec![(ACCELERATE.to_string(), 1)];
+        }
+        vec![]
+    }
+}
+
+// Accelerate, and shell the car just ahead when it is faster
+pub struct Sheller;
+
+impl Strategy for Sheller {
+    fn name(&self) -> &str {
+        "sheller"
+    }
+
+    fn take_turn(&mut self, view: &TurnView) -> Vec<Order> {
+        let mut orders = vec![];
+        let rank = view.rank();
+        if rank > 0 {
+            let ahead = view.standings()[rank - 1];
+            if ahead.speed > view.me().speed && view.can_afford(SHELL, 1) {
+                orders.push((SHELL.to_string(), 1));
+            }
+        }
+        if view.can_afford(ACCELERATE, 1) {
+            orders.push((ACCELERATE.to_string(), 1));
+        }
+        orders
+    }
+}
+
+// Accelerate, drop bananas while leading and keep a shield when a car is close behind
+pub struct Defender;
+
+impl Strategy for Defender {
+    fn name(&self) -> &str {
+        "defender"
+    }
+
+    fn take_turn(&mut self, view: &TurnView) -> Vec<Order> {
+        let mut orders = vec![];
+        let me = view.me();
+        let standings = view.standings();
+        if view.rank() == 0 && standings.len() > 1 {
+            if view.can_afford(BANANA, 1) {
+                orders.push((BANANA.to_string(), 1));
+            }
+            if me.shield == 0 && me.y - standings[1].y < 50 && view.can_afford(SHIELD, 1) {
+                orders.push((SHIELD.to_string(), 1));
+            }
+        }
+        if view.can_afford(ACCELERATE, 1) {
+            orders.push((ACCELERATE.to_string(), 1));
+        }
+        orders
+    }
+}
+
+// The strategy of the parameterized car contract, it sees the turn as the contract does
+pub struct Parameterized {
+    pub name: String,
+    pub params: StrategyParams,
+}
+
+impl Strategy for Parameterized {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn take_turn(&mut self, view: &TurnView) -> Vec<Order> {
+        TurnInfo::from_race(view.race, view.car)
+            .map(|turn| self.params.decide(&turn))
+            .unwrap_or_default()
+    }
+}
+
+// Strategy of a built-in name
+pub fn builtin(name: &str) -> Option<Box<dyn Strategy>> {
+    match name {
+        "accelerator" => Some(Box::new(Accelerator)),
+        "sheller" => Some(Box::new(Sheller)),
+        "defender" => Some(Box::new(Defender)),
+        "parameterized" => Some(Box::new(Parameterized {
+            name: name.to_string(),
+            params: StrategyParams::default(),
+        })),
+        _ => None,
+    }
+}
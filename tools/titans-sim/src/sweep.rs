@@ -0,0 +1,330 @@
+use std::fmt::Write;
+
+use titans_engine::{pricing::WAD, PricingSpec};
+
+use crate::{
+    sim::{simulate, SimConfig, SimReport},
+    strategy::Strategy,
+};
+
+// Values of each pricing field, every combination is simulated. A field without values
+// keeps the pricing of each action. `per_turn_decrease` and `sell_per_turn` are in WAD.
+pub struct Sweep {
+    pub target_price: Vec<u64>,
+    pub per_turn_decrease: Vec<u64>,
+    pub sell_per_turn: Vec<u64>,
+    // Actions the pricing is applied to, all of them when empty
+    pub actions: Vec<String>,
+}
+
+// Pricing fields set by a combination of the sweep, the others are left to the action
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PricingOverride {
+    pub target_price: Option<u64>,
+    pub per_turn_decrease: Option<u64>,
+    pub sell_per_turn: Option<u64>,
+}
+
+impl PricingOverride {
+    pub fn apply(&self, pricing: &mut PricingSpec) {
+        if let Some(target_price) = self.target_price {
+            pricing.target_price = target_price;
+        }
+        if let Some(per_turn_decrease) = self.per_turn_decrease {
+            pricing.per_turn_decrease = per_turn_decrease;
+        }
+        if let Some(sell_per_turn) = self.sell_per_turn {
+            pricing.sell_per_turn = sell_per_turn;
+        }
+    }
+}
+
+pub struct SweepRow {
+    pub pricing: PricingOverride,
+    pub report: SimReport,
+}
+
+impl SweepRow {
+    // Difference between the best and the worst win rate, 0 when the strategies are balanced
+    pub fn win_rate_spread(&self) -> f64 {
+        let win_rates = self.report.strategies.iter().map(|s| s.win_rate);
+        let max = win_rates.clone().fold(f64::MIN, f64::max);
+        let min = win_rates.fold(f64::MAX, f64::min);
+        if self.report.strategies.is_empty() {
+            0.0
+        } else {
+            max - min
+        }
+    }
+
+    // Average turn the finished races ended at
+    pub fn avg_race_length(&self) -> f64 {
+        let wins: u64 = self.report.strategies.iter().map(|s| s.wins).sum();
+        if wins == 0 {
+            return 0.0;
+        }
+        let turns: f64 = self
+            .report
+            .strategies
+            .iter()
+            .map(|s| s.avg_finish_turn * s.wins as f64)
+            .sum();
+        turns / wins as f64
+    }
+}
+
+// Simulate `config.races` races for each pricing of the sweep, with the same seed
+pub fn sweep(
+    config: &SimConfig,
+    sweep: &Sweep,
+    strategies: &mut [Box<dyn Strategy>],
+) -> Vec<SweepRow> {
+    // A single None keeps the field of the actions
+    let values = |values: &[u64]| -> Vec<Option<u64>> {
+        if values.is_empty() {
+            vec![None]
+        } else {
+            values.iter().copied().map(Some).collect()
+        }
+    };
+
+    let mut rows = vec![];
+    for target_price in values(&sweep.target_price) {
+        for per_turn_decrease in values(&sweep.per_turn_decrease) {
+            for sell_per_turn in values(&sweep.sell_per_turn) {
+                let pricing = PricingOverride {
+                    target_price,
+                    per_turn_decrease,
+                    sell_per_turn,
+                };
+
+                let mut rules = config.rules.clone();
+                for action in rules.actions.iter_mut() {
+                    if sweep.actions.is_empty() || sweep.actions.contains(&action.name) {
+                        pricing.apply(&mut action.pricing);
+                    }
+                }
+                let config = SimConfig {
+                    rules,
+                    races: config.races,
+                    max_turns: config.max_turns,
+                    seed: config.seed,
+                };
+
+                rows.push(SweepRow {
+                    pricing,
+                    report: simulate(&config, strategies),
+                });
+            }
+        }
+    }
+    rows
+}
+
+// One line per pricing, with the win rate of each strategy. The fields left to the actions
+// are empty, the WAD ones are written as decimals. A strategy listed several times is named
+// after its seat too, as `name#seat`.
+pub fn to_csv(rows: &[SweepRow]) -> String {
+    let mut csv = String::from(
+        "target_price,per_turn_decrease,sell_per_turn,races,unfinished,win_rate_spread,avg_race_length",
+    );
+    if let Some(row) = rows.first() {
+        let strategies = &row.report.strategies;
+        for (seat, strategy) in strategies.iter().enumerate() {
+            let count = strategies
+                .iter()
+                .filter(|other| other.name == strategy.name)
+                .count();
+            if count > 1 {
+                write!(csv, ",win_rate_{}#{}", strategy.name, seat).unwrap();
+            } else {
+                write!(csv, ",win_rate_{}", strategy.name).unwrap();
+            }
+        }
+    }
+    csv.push('\n');
+
+    for row in rows {
+        write!(
+            csv,
+            "{},{},{},{},{},{:.4},{:.2}",
+            row.pricing
+                .target_price
+                .map(|price| price.to_string())
+                .unwrap_or_default(),
+            row.pricing
+                .per_turn_decrease
+                .map(format_wad)
+                .unwrap_or_default(),
+            row.pricing
+                .sell_per_turn
+                .map(format_wad)
+                .unwrap_or_default(),
+            row.report.races,
+            row.report.unfinished,
+            row.win_rate_spread(),
+            row.avg_race_length()
+        )
+        .unwrap();
+        for strategy in row.report.strategies.iter() {
+            write!(csv, ",{:.4}", strategy.win_rate).unwrap();
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+// Values of a range argument: `10,20,40` or `START:END:STEP`, the end is included
+pub fn parse_range(value: &str) -> Option<Vec<u64>> {
+    parse_values(value, |v| v.parse().ok())
+}
+
+// Values of a range of decimals in WAD: `0.2,0.33` or `0.1:0.5:0.1`
+pub fn parse_wad_range(value: &str) -> Option<Vec<u64>> {
+    parse_values(value, parse_wad)
+}
+
+fn parse_values(value: &str, parse: impl Fn(&str) -> Option<u64>) -> Option<Vec<u64>> {
+    let parts: Vec<&str> = value.split(':').collect();
+    match parts.as_slice() {
+        [start, end, step] => {
+            let (start, end, step) = (parse(start)?, parse(end)?, parse(step)?);
+            if step == 0 || start > end {
+                return None;
+            }
+            Some((start..=end).step_by(step as usize).collect())
+        }
+        [list] => list.split(',').map(|v| parse(v.trim())).collect(),
+        _ => None,
+    }
+}
+
+// `0.33` to 330_000_000_000_000_000, at most 18 decimals
+fn parse_wad(value: &str) -> Option<u64> {
+    let (units, decimals) = value.split_once('.').unwrap_or((value, ""));
+    if decimals.len() > 18 || !decimals.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let units: u64 = units.parse().ok()?;
+    let decimals: u64 = format!("{:0<18}", decimals).parse().ok()?;
+    units.checked_mul(WAD)?.checked_add(decimals)
+}
+
+fn format_wad(value: u64) -> String {
+    let decimals = format!("{:018}", value % WAD);
+    let decimals = decimals.trim_end_matches('0');
+    if decimals.is_empty() {
+        (value / WAD).to_string()
+    } else {
+        format!("{}.{}", value / WAD, decimals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use titans_engine::Rules;
+
+    use super::{parse_range, parse_wad_range, sweep, to_csv, Sweep};
+    use crate::{
+        sim::SimConfig,
+        strategy::{builtin, Strategy},
+    };
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("10,20,40"), Some(vec![10, 20, 40]));
+        assert_eq!(parse_range("7"), Some(vec![7]));
+        assert_eq!(parse_range("0:10:5"), Some(vec![0, 5, 10]));
+        assert_eq!(parse_range("0:9:5"), Some(vec![0, 5]));
+        assert_eq!(parse_range("0:10:0"), None);
+        assert_eq!(parse_range("1,a"), None);
+
+        let wad = 1_000_000_000_000_000_000;
+        assert_eq!(
+            parse_wad_range("0.33,2"),
+            Some(vec![330_000_000_000_000_000, 2 * wad])
+        );
+        assert_eq!(
+            parse_wad_range("0.1:0.3:0.1"),
+            Some(vec![wad / 10, wad / 5, 3 * wad / 10])
+        );
+        assert_eq!(parse_wad_range("0.1234567890123456789"), None);
+        assert_eq!(parse_wad_range("0.-1"), None);
+    }
+
+    #[test]
+    fn test_sweep() {
+        let wad = 1_000_000_000_000_000_000;
+        let config = SimConfig {
+            rules: Rules::default(),
+            races: 20,
+            max_turns: 1000,
+            seed: 3,
+        };
+        let mut strategies: Vec<Box<dyn Strategy>> = ["accelerator", "sheller"]
+            .iter()
+            .map(|name| builtin(name).unwrap())
+            .collect();
+        let rows = sweep(
+            &config,
+            &Sweep {
+                target_price: vec![100, 200],
+                per_turn_decrease: vec![],
+                sell_per_turn: vec![wad / 5, wad / 2, wad],
+                actions: vec!["shell".to_string()],
+            },
+            &mut strategies,
+        );
+
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[5].pricing.target_price, Some(200));
+        assert_eq!(rows[5].pricing.per_turn_decrease, None);
+        assert_eq!(rows[5].pricing.sell_per_turn, Some(wad));
+        for row in rows.iter() {
+            assert!(row.win_rate_spread() >= 0.0 && row.win_rate_spread() <= 1.0);
+            assert!(row.avg_race_length() > 0.0);
+        }
+
+        let csv = to_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(
+            lines[0],
+            "target_price,per_turn_decrease,sell_per_turn,races,unfinished,win_rate_spread,avg_race_length,win_rate_accelerator,win_rate_sheller"
+        );
+        assert!(lines[1].starts_with("100,,0.2,20,"));
+        // The price of the shells changes the races
+        assert_ne!(rows[0].report, rows[5].report);
+    }
+
+    #[test]
+    fn test_csv_duplicate_strategies() {
+        let config = SimConfig {
+            rules: Rules::default(),
+            races: 2,
+            max_turns: 1000,
+            seed: 3,
+        };
+        let mut strategies: Vec<Box<dyn Strategy>> = ["accelerator", "sheller", "accelerator"]
+            .iter()
+            .map(|name| builtin(name).unwrap())
+            .collect();
+        let rows = sweep(
+            &config,
+            &Sweep {
+                target_price: vec![100],
+                per_turn_decrease: vec![],
+                sell_per_turn: vec![],
+                actions: vec![],
+            },
+            &mut strategies,
+        );
+
+        let csv = to_csv(&rows);
+        assert!(csv
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with(",win_rate_accelerator#0,win_rate_sheller,win_rate_accelerator#2"));
+    }
+}